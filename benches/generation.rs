@@ -0,0 +1,37 @@
+use backgammon_cli::backgammon::{Game, perft};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// Positions to generate from, with the player of each on roll.
+const POSITIONS: [(&str, &str, [u8; 2]); 3] = [
+    ("opening", "4HPwATDgc/ABMA", [3, 1]),
+    ("doubles", "4HPwATDgc/ABMA", [2, 2]),
+    ("bar", "yHPwATDgOfgAWA", [6, 4]),
+];
+
+fn available_plays(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_available_plays");
+    for (name, id, dice) in POSITIONS {
+        let game = Game::from_position_id(id, dice).unwrap();
+        group.bench_function(name, |b| b.iter(|| black_box(&game).get_available_plays()));
+    }
+    group.finish();
+}
+
+fn available_turns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_available_turns");
+    for (name, id, dice) in POSITIONS {
+        let game = Game::from_position_id(id, dice).unwrap();
+        group.bench_function(name, |b| b.iter(|| black_box(&game).get_available_turns()));
+    }
+    group.finish();
+}
+
+fn perft_opening(c: &mut Criterion) {
+    c.bench_function("perft opening 1", |b| {
+        b.iter(|| perft(black_box("4HPwATDgc/ABMA"), 1));
+    });
+}
+
+criterion_group!(benches, available_plays, available_turns, perft_opening);
+criterion_main!(benches);
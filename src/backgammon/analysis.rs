@@ -0,0 +1,291 @@
+//! Analysis of a finished game, measuring each turn against the best turn
+//! available and each roll against the average roll.
+
+use std::fmt::Display;
+
+use crate::backgammon::{
+    bot::evaluate,
+    compact::CompactBoard,
+    dice_roll::DiceRoll,
+    game::Game,
+    notation::Turn,
+    perft::{dice, rolls},
+    player::Player,
+    rules::Rules,
+};
+
+/// The equity a turn may lose before it is flagged as doubtful.
+pub const DOUBTFUL: f64 = 0.04;
+/// The equity a turn may lose before it is flagged as a blunder.
+pub const BLUNDER: f64 = 0.08;
+/// The evaluation, in pips, of a position worth about three quarters of a
+/// game, used to turn evaluations into equities.
+const SCALE: f64 = 50.0;
+
+/// A turn played in a game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub player: Player,
+    pub dice: [u8; 2],
+    pub turn: Turn,
+}
+
+/// How bad a turn was, for turns losing enough equity to mention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Flag {
+    Doubtful,
+    Blunder,
+}
+
+/// The analysis of a single turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    /// The number of the turn in the game, counting from one
+    pub number: usize,
+    pub player: Player,
+    pub dice: [u8; 2],
+    /// The turn played, in standard notation
+    pub played: String,
+    /// The best turn available, in standard notation
+    pub best: String,
+    /// The number of distinct turns that were available
+    pub choices: usize,
+    /// The equity lost by playing this turn instead of the best one
+    pub loss: f64,
+    /// The equity gained by the roll compared to the average roll
+    pub luck: f64,
+}
+
+impl Decision {
+    /// Gets how bad the turn was, if it lost enough equity to mention.
+    #[must_use]
+    pub fn flag(&self) -> Option<Flag> {
+        match self.loss {
+            loss if loss >= BLUNDER => Some(Flag::Blunder),
+            loss if loss >= DOUBTFUL => Some(Flag::Doubtful),
+            _ => None,
+        }
+    }
+
+    /// Checks whether there was more than one turn to choose from.
+    #[must_use]
+    pub const fn is_choice(&self) -> bool {
+        self.choices > 1
+    }
+}
+
+/// The analysis of every turn of a game.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Analysis {
+    pub decisions: Vec<Decision>,
+}
+
+impl Analysis {
+    /// Replays the turns of a game from its start, evaluating each against
+    /// the best available turn with the heuristic evaluation.
+    #[must_use]
+    pub fn new(start: &Game, history: &[Record]) -> Self {
+        let mut game = start.clone();
+        let mut decisions = vec![];
+
+        for (index, record) in history.iter().enumerate() {
+            game.current_player = record.player;
+            game.dice_roll = DiceRoll::from(record.dice);
+
+            let player = record.player;
+            let before = CompactBoard::from(&game.board);
+            let available = game.get_available_turns();
+            let (best, best_equity) = available
+                .iter()
+                .map(|(turn, board)| (turn, equity(board, player)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or_else(
+                    || (Turn(vec![]), equity(&before, player)),
+                    |(turn, equity)| (turn.clone(), equity),
+                );
+
+            let notation = record.turn.notation(&game.board);
+            let best = best.notation(&game.board);
+            game.take_turn(&record.turn);
+            let loss = best_equity - equity(&CompactBoard::from(&game.board), player);
+
+            decisions.push(Decision {
+                number: index + 1,
+                player,
+                dice: record.dice,
+                played: notation,
+                best,
+                choices: available.len(),
+                loss: loss.max(0.0),
+                luck: best_equity - average_equity(&before, player, game.rules),
+            });
+        }
+
+        Self { decisions }
+    }
+
+    /// Gets the decisions of a player where they had a choice to make.
+    fn choices(&self, player: Player) -> impl Iterator<Item = &Decision> {
+        self.decisions
+            .iter()
+            .filter(move |decision| decision.player == player && decision.is_choice())
+    }
+
+    /// Gets the average equity a player lost per decision, in thousandths.
+    #[must_use]
+    pub fn error_rate(&self, player: Player) -> f64 {
+        let (count, loss) = self
+            .choices(player)
+            .fold((0.0, 0.0), |(count, loss), decision| {
+                (count + 1.0, loss + decision.loss)
+            });
+        if count > 0.0 {
+            loss / count * 1000.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Gets the total equity a player gained from their rolls compared to
+    /// average rolls, where a lucky player has a positive rating.
+    #[must_use]
+    pub fn luck(&self, player: Player) -> f64 {
+        self.decisions
+            .iter()
+            .filter(|decision| decision.player == player)
+            .fold(0.0, |luck, decision| luck + decision.luck)
+    }
+
+    /// Counts the turns of a player with the given flag.
+    #[must_use]
+    pub fn count(&self, player: Player, flag: Flag) -> usize {
+        self.choices(player)
+            .filter(|decision| decision.flag() == Some(flag))
+            .count()
+    }
+}
+
+/// Converts the heuristic evaluation of a board into an equity between -1 and
+/// 1 for the player, ignoring gammons.
+pub fn equity(board: &CompactBoard, player: Player) -> f64 {
+    (evaluate(board, player) / SCALE).tanh()
+}
+
+/// Gets the equity of the best turn of each roll under the given rules,
+/// weighted by how likely the roll is.
+fn average_equity(board: &CompactBoard, player: Player, rules: Rules) -> f64 {
+    rolls()
+        .map(|roll @ [a, b]| {
+            let best = board
+                .turns(player, &dice(roll), rules)
+                .iter()
+                .map(|turn| equity(&turn.board, player))
+                .fold(equity(board, player), f64::max);
+            let weight = if a == b { 1.0 } else { 2.0 };
+            best * weight / 36.0
+        })
+        .sum()
+}
+
+impl Display for Flag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Doubtful => "doubtful",
+            Self::Blunder => "blunder",
+        })
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Analysis")?;
+        for decision in &self.decisions {
+            let Some(flag) = decision.flag() else {
+                continue;
+            };
+            let [a, b] = decision.dice;
+            writeln!(
+                f,
+                "  {:>3}. {} {a}-{b} {}: {flag}, lost {:.3} (best {})",
+                decision.number, decision.player, decision.played, decision.loss, decision.best,
+            )?;
+        }
+
+        for player in [Player::Black, Player::White] {
+            write!(
+                f,
+                "\n{player}: {} decisions, {} doubtful, {} blunders, error rate {:.1}, luck {:+.3}",
+                self.choices(player).count(),
+                self.count(player, Flag::Doubtful),
+                self.count(player, Flag::Blunder),
+                self.error_rate(player),
+                self.luck(player),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{
+        board::Board,
+        notation::{Play, PositionRef, turn},
+    };
+
+    fn record(player: Player, dice: [u8; 2], turn: Turn) -> Record {
+        Record { player, dice, turn }
+    }
+
+    #[test]
+    fn best_turn_loses_nothing() {
+        let player = Player::Black;
+        let game = Game::from(player, DiceRoll::from([3, 1]), Board::new());
+        let history = [record(player, [3, 1], turn!(player, (7, 4), (5, 4)))];
+
+        let analysis = Analysis::new(&game, &history);
+        let decision = &analysis.decisions[0];
+        assert_eq!(decision.played, decision.best);
+        assert!(decision.loss.abs() < f64::EPSILON);
+        assert_eq!(decision.flag(), None);
+        assert!(analysis.error_rate(player).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn flags_blunder() {
+        let player = Player::Black;
+        let game = Game::from(player, DiceRoll::from([3, 1]), Board::new());
+        let history = [
+            record(player, [3, 1], turn!(player, (23, 20), (20, 19))),
+            record(!player, [2, 1], turn!(!player, (0, 2), (0, 1))),
+        ];
+
+        let analysis = Analysis::new(&game, &history);
+        assert_eq!(analysis.decisions[0].best, "8/5 6/5");
+        assert_eq!(analysis.decisions[0].flag(), Some(Flag::Blunder));
+        assert_eq!(analysis.count(player, Flag::Blunder), 1);
+        assert!(analysis.error_rate(player) >= BLUNDER * 1000.0);
+        assert_eq!(analysis.decisions[1].number, 2);
+        assert!(analysis.to_string().contains("24/21 21/20: blunder"));
+    }
+
+    #[test]
+    fn forced_turns_are_not_decisions() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.bar_mut(player).set(1, player);
+        for index in 18..23 {
+            board.point_mut(index).set(2, !player);
+        }
+        board.point_mut(0).set(4, !player);
+        board.point_mut(10).set(14, player);
+        let game = Game::from(player, DiceRoll::from([6, 5]), board);
+        let history = [record(player, [6, 5], Turn(vec![]))];
+
+        let analysis = Analysis::new(&game, &history);
+        assert_eq!(analysis.decisions[0].choices, 1);
+        assert_eq!(analysis.choices(player).count(), 0);
+        assert!(analysis.luck(player) < 0.0);
+    }
+}
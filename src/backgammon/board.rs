@@ -1,27 +1,49 @@
-use colored::Colorize;
 use itertools::Itertools;
 use std::fmt::Debug;
 use std::iter;
 
 use crate::backgammon::{
-    location::{Denormalized, Index, Normalized},
+    Error, Violation,
+    location::{Denormalized, Index, Normalized, Track},
     notation::PositionRef,
     player::Player,
+    theme::{Layout, Theme},
+    variant::Variant,
 };
 
 pub const BOARD_SIZE: usize = 24;
 
-const HOME_BOARD_INDEX: usize = 5;
+/// The number of pieces each player has in a standard game.
+pub const CHECKERS: u32 = 15;
+
+/// The most checkers drawn on a point before the rest are shown as a count.
+pub const STACK_HEIGHT: u8 = 5;
+
+/// The last point of a player's home board.
+const HOME_BOARD: usize = 6;
+
+/// The location of a player's bar from their perspective.
+const BAR: usize = BOARD_SIZE + 1;
 
 #[derive(Debug, Clone)]
 pub struct Board {
     points: [Position; BOARD_SIZE],
     bar: [Position; 2],
     rail: [Position; 2],
+    /// How the players' paths run around the points
+    track: Track,
+    /// The number of pieces each player has
+    checkers: u32,
 }
 
 impl Board {
     pub fn empty() -> Self {
+        Self::empty_on(Track::Opposite)
+    }
+
+    /// Creates a board without pieces whose players move along the given
+    /// track.
+    pub fn empty_on(track: Track) -> Self {
         let points: [_; BOARD_SIZE] = (0..BOARD_SIZE)
             .map(|i| Position::new(Index::try_from(i).unwrap().denormalize(), 0, Player::None))
             .collect::<Vec<_>>()
@@ -46,26 +68,18 @@ impl Board {
             ),
         ];
 
-        Self { points, bar, rail }
+        Self {
+            points,
+            bar,
+            rail,
+            track,
+            checkers: CHECKERS,
+        }
     }
 
+    /// Creates the standard starting position.
     pub fn new() -> Self {
-        let mut board = Self::empty();
-
-        // Sets black pieces.
-        board.point_mut(23).set(2, Player::Black);
-        board.point_mut(12).set(5, Player::Black);
-        board.point_mut(7).set(3, Player::Black);
-        board.point_mut(5).set(5, Player::Black);
-
-        // Sets white pieces.
-        let offset = BOARD_SIZE - 1;
-        board.point_mut(offset - 23).set(2, Player::White);
-        board.point_mut(offset - 12).set(5, Player::White);
-        board.point_mut(offset - 7).set(3, Player::White);
-        board.point_mut(offset - 5).set(5, Player::White);
-
-        board
+        Variant::Standard.board()
     }
 
     pub const fn bar(&self, player: Player) -> &Position {
@@ -80,17 +94,61 @@ impl Board {
         &self.points[index]
     }
 
-    pub fn bar_mut(&mut self, player: Player) -> &mut Position {
+    pub const fn bar_mut(&mut self, player: Player) -> &mut Position {
         &mut self.bar[player as usize]
     }
 
-    pub fn rail_mut(&mut self, player: Player) -> &mut Position {
+    pub const fn rail_mut(&mut self, player: Player) -> &mut Position {
         &mut self.rail[player as usize]
     }
 
-    pub fn point_mut(&mut self, index: usize) -> &mut Position {
+    pub const fn point_mut(&mut self, index: usize) -> &mut Position {
         &mut self.points[index]
     }
+
+    /// Gets how the players' paths run around the points.
+    pub const fn track(&self) -> Track {
+        self.track
+    }
+
+    /// Gets the number of pieces each player has, which is `CHECKERS` unless
+    /// a variant starts with fewer.
+    pub const fn checkers(&self) -> u32 {
+        self.checkers
+    }
+
+    /// Sets the number of pieces each player has.
+    pub const fn set_checkers(&mut self, checkers: u32) {
+        self.checkers = checkers;
+    }
+
+    /// Gets the index of a point given from the perspective of a player.
+    pub fn index(&self, point: usize, player: Player) -> Result<Index, Error> {
+        Normalized::new(point, player)?.to_index_on(self.track)
+    }
+
+    /// Gets a location from 0 to 25 given from the perspective of a player,
+    /// where 0 is their rail and 25 is their bar.
+    pub fn position_ref(&self, location: usize, player: Player) -> PositionRef {
+        match location {
+            0 => PositionRef::Rail(player),
+            BAR => PositionRef::Bar(player),
+            point => PositionRef::Point(
+                self.index(point, player)
+                    .expect("point should be indexable"),
+            ),
+        }
+    }
+
+    /// Gets the location of a position from the perspective of a player, from
+    /// 0 for their rail to 25 for their bar.
+    pub fn locate(&self, position: &PositionRef, player: Player) -> usize {
+        match *position {
+            PositionRef::Rail(_) => 0,
+            PositionRef::Bar(_) => BAR,
+            PositionRef::Point(index) => *index.normalize_on(player, self.track),
+        }
+    }
     // impl Space {
     //     pub fn position<'a>(&self, board: &'a Board) -> &'a Position {
     //         match *self {
@@ -124,154 +182,376 @@ impl Board {
         }
     }
 
-    pub fn any_behind(&self, index: usize, player: Player) -> bool {
-        let any = |p: &Position| p.player == player && p.count > 0;
-        match player {
-            Player::Black => self.points[index + 1..]
-                .iter()
-                .chain(iter::once(self.bar(player)))
-                .any(any),
-            Player::White => iter::once(self.bar(player))
-                .chain(self.points[..index].iter())
-                .any(any),
-            Player::None => panic!("no pieces behind `None`"),
-        }
+    /// Checks whether the player has any piece further from home than the
+    /// given point from their perspective, including on the bar and pinned
+    /// beneath the opponent.
+    pub fn any_behind(&self, point: usize, player: Player) -> bool {
+        assert!(player != Player::None, "no pieces behind `None`");
+        let any =
+            |p: &Position| (p.player == player && p.count > 0) || (p.player == !player && p.pinned);
+        let behind = (point + 1..=BOARD_SIZE).map(|point| {
+            let index = self
+                .index(point, player)
+                .expect("point should be indexable");
+            self.point(*index)
+        });
+        iter::once(self.bar(player)).chain(behind).any(any)
     }
 
     pub fn all_in_home(&self, player: Player) -> bool {
-        let index = match player {
-            Player::Black => HOME_BOARD_INDEX,
-            Player::White => BOARD_SIZE - 1 - HOME_BOARD_INDEX,
-            Player::None => panic!("no pieces behind `None`"),
-        };
-
-        !self.any_behind(index, player)
+        !self.any_behind(HOME_BOARD, player)
     }
 
     pub fn all_in_rail(&self, player: Player) -> bool {
-        let positions = self
-            .points
+        u32::from(self.rail(player).count) == self.checker_count(player)
+    }
+
+    /// Gets the total number of pieces the player has across the points, bar,
+    /// and rail.
+    pub fn checker_count(&self, player: Player) -> u32 {
+        self.points
             .iter()
             .chain(self.bar.iter())
-            .chain(self.rail.iter());
-        let total = positions
-            .filter_map(|p| {
-                if p.player == player {
-                    Some(p.count)
-                } else {
-                    None
-                }
+            .chain(self.rail.iter())
+            .map(|p| u32::from(p.pieces(player)))
+            .sum()
+    }
+
+    /// Gets the total number of pips the player must move to bear off every
+    /// piece, counting pieces in the bar as being on the 25 point and
+    /// including any pinned beneath the opponent.
+    pub fn pip_count(&self, player: Player) -> usize {
+        (1..=BAR)
+            .map(|location| {
+                let position = self.get(&self.position_ref(location, player));
+                location * usize::from(position.pieces(player))
             })
-            .sum();
+            .sum()
+    }
+
+    /// Checks that every invariant of the board holds, returning an error
+    /// describing each violation otherwise.
+    pub fn validate(&self) -> Result<(), Error> {
+        let violations = self.violations();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidBoard(violations))
+        }
+    }
+
+    /// Gets every broken invariant of the board: each player has exactly
+    /// `checkers` pieces, points are owned if and only if they hold pieces,
+    /// and bars and rails only hold pieces of their player.
+    pub fn violations(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        for player in [Player::Black, Player::White] {
+            let count = self.checker_count(player);
+            if count != self.checkers {
+                violations.push(Violation::CheckerCount(player, count, self.checkers));
+            }
+
+            let bar = self.bar(player);
+            if bar.player != player {
+                violations.push(Violation::BarOwner(player, bar.player));
+            }
+
+            let rail = self.rail(player);
+            if rail.player != player {
+                violations.push(Violation::RailOwner(player, rail.player));
+            }
+        }
+
+        for (i, point) in self.points.iter().enumerate() {
+            match (point.count, point.player) {
+                (0, Player::None) => {}
+                (0, player) => violations.push(Violation::EmptyPointOwned(i + 1, player)),
+                (count, Player::None) => violations.push(Violation::UnownedPieces(i + 1, count)),
+                _ => {}
+            }
+        }
 
-        self.rail(player).count == total
+        violations
     }
 }
 
-impl std::fmt::Display for Board {
-    #[allow(unstable_name_collisions)]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let index_format = |i| format!("{i:02}");
-        let point_format = |point: &Position| {
-            let count = point.count;
+impl Board {
+    /// Draws the board with the given theme from the perspective of a player,
+    /// whose home board is in the bottom right for `Player::Black` and the
+    /// bottom left for `Player::White`.
+    #[must_use]
+    pub fn render(&self, perspective: Player, theme: Theme) -> String {
+        match theme.layout {
+            Layout::Grid => self.render_grid(perspective, theme),
+            Layout::Stacked => self.render_stacked(perspective, theme),
+        }
+    }
 
-            let str = if count == 0 {
-                format!("{:2}", "░░")
+    /// Draws the board as a grid of piece counts.
+    fn render_grid(&self, perspective: Player, theme: Theme) -> String {
+        let width = theme.cell_width();
+        let blank = " ".repeat(width);
+
+        let cell = |position: &Position| theme.cell(position.count, position.player);
+        let point = |point| {
+            let index = self
+                .index(point, perspective)
+                .expect("point should be indexable");
+            let position = &self.points[*index];
+            if position.pinned {
+                theme.pinned_cell(position.count, position.player)
             } else {
-                format!("{count:02}")
-            };
+                cell(position)
+            }
+        };
+        let number = |point| format!("{:>width$}", format!("{point:02}"));
+        let table = |points: &[usize], format: &dyn Fn(usize) -> String| {
+            points.iter().map(|&point| format(point)).join(" ")
+        };
 
-            let str = match point.player {
-                Player::Black => str.on_black().white().bold(),
-                Player::White => str.on_white().black().bold(),
-                Player::None => str.normal().dimmed(),
-            };
-            format!("{str}")
+        let mirrored = perspective == Player::White;
+        let [top, bottom] = tables(perspective);
+        let [top, bottom] = [[&top[0], &top[1]], [&bottom[0], &bottom[1]]];
+
+        let (top_side, bottom_side) = (cell(self.rail(!perspective)), cell(self.rail(perspective)));
+        let sides = |rail: String| {
+            if mirrored {
+                [rail, blank.clone()]
+            } else {
+                [blank.clone(), rail]
+            }
         };
+        let [top_left, top_right] = sides(top_side);
+        let [bottom_left, bottom_right] = sides(bottom_side);
+
+        let rows = [
+            [
+                blank.clone(),
+                table(top[0], &number),
+                blank.clone(),
+                table(top[1], &number),
+                blank.clone(),
+            ],
+            [
+                top_left,
+                table(top[0], &point),
+                cell(self.bar(perspective)),
+                table(top[1], &point),
+                top_right,
+            ],
+            [
+                bottom_left,
+                table(bottom[0], &point),
+                cell(self.bar(!perspective)),
+                table(bottom[1], &point),
+                bottom_right,
+            ],
+            [
+                blank.clone(),
+                table(bottom[0], &number),
+                blank.clone(),
+                table(bottom[1], &number),
+                blank,
+            ],
+        ];
 
-        let perspective = if f.alternate() {
-            Player::White
-        } else {
-            Player::Black
+        frame(&rows, theme)
+    }
+
+    /// Draws the board as columns of checkers stacking toward the center, with
+    /// the bar between the tables and the borne off pieces in a tray beside
+    /// the player's home board.
+    fn render_stacked(&self, perspective: Player, theme: Theme) -> String {
+        let glyphs = theme.glyphs();
+        let mirrored = perspective == Player::White;
+        let [top, bottom] = tables(perspective);
+
+        let column = |position: &Position| stack(position, theme);
+        let point = |point| {
+            let index = self
+                .index(point, perspective)
+                .expect("point should be indexable");
+            column(&self.points[*index])
+        };
+        // Stacks are drawn from the top, so the bottom half is flipped.
+        let half = |points: &[usize], bar: &Position, tray: &Position, flip: bool| {
+            let mut stacks: Vec<Vec<String>> = points.iter().map(|&p| point(p)).collect();
+            // Pieces on the bar wait beside it in the middle of the board.
+            stacks.insert(6, column(bar).into_iter().rev().collect());
+            stacks.push(column(tray));
+            if flip {
+                for stack in &mut stacks {
+                    stack.reverse();
+                }
+            }
+            (0..usize::from(STACK_HEIGHT))
+                .map(|row| stacks.iter().map(|stack| stack[row].clone()).collect())
+                .collect::<Vec<Vec<String>>>()
         };
 
-        macro_rules! bkgm_format {
-            ($range:expr $(, $rev:ident)?) => {
-                (bkgm_format!(index_format, $range $(, $rev)?), bkgm_format!(|i| {
-                    point_format(&self.points[
-                        *Normalized::new(i, perspective)
-                            .unwrap()
-                            .to_index()
-                            .unwrap()
-                    ])
-                }, $range $(, $rev)?))
+        let top_points = [top[0].as_slice(), &top[1]].concat();
+        let bottom_points = [bottom[0].as_slice(), &bottom[1]].concat();
+        let top_rows = half(
+            &top_points,
+            self.bar(perspective),
+            self.rail(!perspective),
+            false,
+        );
+        let bottom_rows = half(
+            &bottom_points,
+            self.bar(!perspective),
+            self.rail(perspective),
+            true,
+        );
+
+        let vertical = glyphs.vertical.to_string();
+        let horizontal = glyphs.horizontal.to_string();
+        let line = |tables: [&[String]; 2], bar: &str, tray: &str| {
+            let table = |cells: &[String]| format!(" {} ", cells.join(" "));
+            let mut columns = vec![table(tables[0]), bar.to_owned(), table(tables[1])];
+            if mirrored {
+                columns.insert(0, tray.to_owned());
+            } else {
+                columns.push(tray.to_owned());
+            }
+            format!("{vertical}{}{vertical}", columns.join(&vertical))
+        };
+        let row = |cells: &Vec<String>| {
+            line(
+                [&cells[..6], &cells[7..13]],
+                &format!("{} ", cells[6]),
+                &format!("{} ", cells[13]),
+            )
+        };
+        let border = |[left, middle, right]: [char; 3], points: &[Vec<usize>; 2]| {
+            let table = |points: &[usize]| {
+                let numbers = points.iter().map(|point| format!("{point:>2}")).join(" ");
+                format!(" {numbers} ").replace(' ', &horizontal)
             };
-            ($fmt:expr, $range:expr $(, $rev:ident)?) => {
-                ($range).map($fmt)$(.$rev())?.intersperse(" ".to_string()).collect::<String>()
+            let mut columns = vec![table(&points[0]), horizontal.repeat(3), table(&points[1])];
+            if mirrored {
+                columns.insert(0, horizontal.repeat(3));
+            } else {
+                columns.push(horizontal.repeat(3));
+            }
+            format!("{left}{}{right}", columns.join(&middle.to_string()))
+        };
+
+        let empty = vec!["  ".to_string(); 6];
+        let mut lines = vec![border(glyphs.top, &top)];
+        lines.extend(top_rows.iter().map(row));
+        lines.push(line([&empty, &empty], "BAR", "OFF"));
+        lines.extend(bottom_rows.iter().map(row));
+        lines.push(border(glyphs.bottom, &bottom));
+        lines.join("\n")
+    }
+}
+
+/// Gets the points of the top and bottom halves of the board from the
+/// perspective of a player, each as its left and right tables in the order
+/// they are drawn.
+pub fn tables(perspective: Player) -> [[Vec<usize>; 2]; 2] {
+    let mirrored = perspective == Player::White;
+    let quarter = |range: std::ops::RangeInclusive<usize>, reversed: bool| -> Vec<usize> {
+        if reversed {
+            range.rev().collect()
+        } else {
+            range.collect()
+        }
+    };
+
+    let top = [quarter(13..=18, mirrored), quarter(19..=24, mirrored)];
+    let bottom = [quarter(7..=12, !mirrored), quarter(1..=6, !mirrored)];
+    if mirrored {
+        let ([outer, home], [outer_bottom, home_bottom]) = (top, bottom);
+        [[home, outer], [home_bottom, outer_bottom]]
+    } else {
+        [top, bottom]
+    }
+}
+
+/// Draws the pieces of a position as a stack of two character cells from the
+/// edge of the board, with the last cell counting every piece on a tall stack.
+/// A pinned piece is drawn at the edge, beneath the pieces pinning it.
+fn stack(position: &Position, theme: Theme) -> Vec<String> {
+    let pinned = u8::from(position.pinned);
+    let count = position.count + pinned;
+    (0..STACK_HEIGHT)
+        .map(|row| match row {
+            _ if row + 1 == STACK_HEIGHT && count > STACK_HEIGHT => format!("{count:>2}"),
+            _ if row < pinned => format!(" {}", theme.checker(!position.player)),
+            _ if row < count => format!(" {}", theme.checker(position.player)),
+            _ => "  ".to_string(),
+        })
+        .collect()
+}
+
+/// Draws the lines around the rows of a board, each holding the contents of
+/// the left side, a table, the bar, the other table and the right side.
+fn frame(rows: &[[String; 5]; 4], theme: Theme) -> String {
+    let glyphs = theme.glyphs();
+    let width = theme.cell_width();
+
+    // Columns alternate between the sides and bar, and the tables.
+    let widths = [width, 6 * width + 5].map(|width| width + 2);
+    let widths = [widths[0], widths[1], widths[0], widths[1], widths[0]];
+    let is_table = |column: usize| column % 2 == 1;
+
+    let border = |[left, middle, right]: [char; 3]| {
+        let fill = widths
+            .iter()
+            .map(|&width| glyphs.horizontal.to_string().repeat(width))
+            .join(&middle.to_string());
+        format!("{left}{fill}{right}")
+    };
+    let separator = || {
+        let mut line = glyphs.vertical.to_string();
+        for (column, &width) in widths.iter().enumerate() {
+            let fill = if is_table(column) {
+                glyphs.horizontal
+            } else {
+                ' '
             };
+            line.push_str(&fill.to_string().repeat(width));
+            line.push(match column {
+                4 => glyphs.vertical,
+                _ if is_table(column + 1) => glyphs.middle[0],
+                _ => glyphs.middle[1],
+            });
         }
+        line
+    };
+    let content = |row: &[String; 5]| {
+        let vertical = glyphs.vertical.to_string();
+        let cells = row.iter().map(|cell| format!(" {cell} ")).join(&vertical);
+        format!("{vertical}{cells}{vertical}")
+    };
+
+    [
+        border(glyphs.top),
+        content(&rows[0]),
+        separator(),
+        content(&rows[1]),
+        separator(),
+        content(&rows[2]),
+        separator(),
+        content(&rows[3]),
+        border(glyphs.bottom),
+    ]
+    .join("\n")
+}
 
-        if f.alternate() {
-            let player_home = bkgm_format!(1..=6);
-            let player_outer = bkgm_format!(7..=12);
-            let opponent_outer = bkgm_format!(13..=18, rev);
-            let opponent_home = bkgm_format!(19..=24, rev);
-
-            write!(
-                f,
-                "┏━━━━┳━━━━━━━━━━━━━━━━━━━┳━━━━┳━━━━━━━━━━━━━━━━━━━┳━━━━┓\n\
-                 ┃    ┃ {               } ┃    ┃ {               } ┃    ┃\n\
-                 ┃    ┣━━━━━━━━━━━━━━━━━━━┫    ┣━━━━━━━━━━━━━━━━━━━┫    ┃\n\
-                 ┃ {} ┃ {               } ┃ {} ┃ {               } ┃    ┃\n\
-                 ┃    ┣━━━━━━━━━━━━━━━━━━━┫    ┣━━━━━━━━━━━━━━━━━━━┫    ┃\n\
-                 ┃ {} ┃ {               } ┃ {} ┃ {               } ┃    ┃\n\
-                 ┃    ┣━━━━━━━━━━━━━━━━━━━┫    ┣━━━━━━━━━━━━━━━━━━━┫    ┃\n\
-                 ┃    ┃ {               } ┃    ┃ {               } ┃    ┃\n\
-                 ┗━━━━┻━━━━━━━━━━━━━━━━━━━┻━━━━┻━━━━━━━━━━━━━━━━━━━┻━━━━┛",
-                opponent_home.0,
-                opponent_outer.0,
-                point_format(self.rail(!perspective)),
-                opponent_home.1,
-                point_format(self.bar(perspective)),
-                opponent_outer.1,
-                point_format(self.rail(perspective)),
-                player_home.1,
-                point_format(self.bar(!perspective)),
-                player_outer.1,
-                player_home.0,
-                player_outer.0,
-            )
+/// Displays the board with the current theme from the perspective of
+/// `Player::Black`, or `Player::White` with the alternate flag (`{:#}`).
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let perspective = if f.alternate() {
+            Player::White
         } else {
-            let player_home = bkgm_format!(1..=6, rev);
-            let player_outer = bkgm_format!(7..=12, rev);
-            let opponent_outer = bkgm_format!(13..=18);
-            let opponent_home = bkgm_format!(19..=24);
-
-            write!(
-                f,
-                "┏━━━━┳━━━━━━━━━━━━━━━━━━━┳━━━━┳━━━━━━━━━━━━━━━━━━━┳━━━━┓\n\
-                 ┃    ┃ {               } ┃    ┃ {               } ┃    ┃\n\
-                 ┃    ┣━━━━━━━━━━━━━━━━━━━┫    ┣━━━━━━━━━━━━━━━━━━━┫    ┃\n\
-                 ┃    ┃ {               } ┃ {} ┃ {               } ┃ {} ┃\n\
-                 ┃    ┣━━━━━━━━━━━━━━━━━━━┫    ┣━━━━━━━━━━━━━━━━━━━┫    ┃\n\
-                 ┃    ┃ {               } ┃ {} ┃ {               } ┃ {} ┃\n\
-                 ┃    ┣━━━━━━━━━━━━━━━━━━━┫    ┣━━━━━━━━━━━━━━━━━━━┫    ┃\n\
-                 ┃    ┃ {               } ┃    ┃ {               } ┃    ┃\n\
-                 ┗━━━━┻━━━━━━━━━━━━━━━━━━━┻━━━━┻━━━━━━━━━━━━━━━━━━━┻━━━━┛",
-                opponent_outer.0,
-                opponent_home.0,
-                opponent_outer.1,
-                point_format(self.bar(perspective)),
-                opponent_home.1,
-                point_format(self.rail(!perspective)),
-                player_outer.1,
-                point_format(self.bar(!perspective)),
-                player_home.1,
-                point_format(self.rail(perspective)),
-                player_outer.0,
-                player_home.0,
-            )
-        }
+            Player::Black
+        };
+        f.write_str(&self.render(perspective, Theme::current()))
     }
 }
 
@@ -287,7 +567,7 @@ impl PartialEq for Board {
 
         let rails_match = self.rail.iter().zip(other.rail.iter()).all(|(a, b)| a == b);
 
-        points_match && bars_match && rails_match
+        points_match && bars_match && rails_match && self.track == other.track
     }
 }
 
@@ -296,6 +576,9 @@ pub struct Position {
     pub location: Denormalized,
     pub count: u8,
     pub player: Player,
+    /// Whether a single piece of the opponent of `player` is pinned beneath
+    /// their pieces, which it cannot leave until they all do
+    pub pinned: bool,
 }
 
 impl Position {
@@ -304,6 +587,7 @@ impl Position {
             location,
             count,
             player,
+            pinned: false,
         }
     }
 
@@ -315,19 +599,84 @@ impl Position {
     //     }
     // }
 
-    pub fn distance(&self, to: &Self) -> usize {
-        self.location.abs_diff(*to.location)
-    }
-
-    pub fn set(&mut self, count: u8, player: Player) {
+    pub const fn set(&mut self, count: u8, player: Player) {
         self.count = count;
         self.player = player;
     }
+
+    /// Gets the number of pieces of the player on the position, including one
+    /// pinned beneath the opponent.
+    fn pieces(&self, player: Player) -> u8 {
+        match self.player {
+            owner if owner == player => self.count,
+            owner if owner == !player && self.pinned => 1,
+            _ => 0,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backgammon::theme::{Borders, Palette};
+
+    #[test]
+    fn render_plain() {
+        let theme = Theme {
+            borders: Borders::Ascii,
+            palette: Palette::None,
+            layout: Layout::Grid,
+        };
+        let mut board = Board::new();
+        board.bar_mut(Player::White).set(1, Player::White);
+        board.point_mut(5).set(4, Player::Black);
+
+        assert_eq!(
+            board.render(Player::Black, theme),
+            "\
++-----+-------------------------+-----+-------------------------+-----+
+|     |  13  14  15  16  17  18 |     |  19  20  21  22  23  24 |     |
+|     +-------------------------+     +-------------------------+     |
+|     | X5  ... ... ... O3  ... | ... | O5  ... ... ... ... X2  | ... |
+|     +-------------------------+     +-------------------------+     |
+|     | O5  ... ... ... X3  ... | O1  | X4  ... ... ... ... O2  | ... |
+|     +-------------------------+     +-------------------------+     |
+|     |  12  11  10  09  08  07 |     |  06  05  04  03  02  01 |     |
++-----+-------------------------+-----+-------------------------+-----+"
+        );
+    }
+
+    #[test]
+    fn render_stacked() {
+        let theme = Theme {
+            borders: Borders::Ascii,
+            palette: Palette::None,
+            layout: Layout::Stacked,
+        };
+        let mut board = Board::empty();
+        board.point_mut(5).set(7, Player::Black);
+        board.point_mut(23).set(2, Player::White);
+        board.bar_mut(Player::Black).set(1, Player::Black);
+        board.rail_mut(Player::White).set(13, Player::White);
+
+        assert_eq!(
+            board.render(Player::Black, theme),
+            "\
++-13-14-15-16-17-18-+---+-19-20-21-22-23-24-+---+
+|                   |   |                 O | O |
+|                   |   |                 O | O |
+|                   |   |                   | O |
+|                   |   |                   | O |
+|                   | X |                   |13 |
+|                   |BAR|                   |OFF|
+|                   |   |  7                |   |
+|                   |   |  X                |   |
+|                   |   |  X                |   |
+|                   |   |  X                |   |
+|                   |   |  X                |   |
++-12-11-10--9--8--7-+---+--6--5--4--3--2--1-+---+"
+        );
+    }
 
     #[test]
     fn all_in_home_1() {
@@ -398,4 +747,52 @@ mod tests {
         println!("{board}");
         assert!(!board.all_in_rail(player));
     }
+
+    #[test]
+    fn pip_count_1() {
+        let board = Board::new();
+        assert_eq!(board.pip_count(Player::Black), 167);
+        assert_eq!(board.pip_count(Player::White), 167);
+    }
+
+    #[test]
+    fn pip_count_2() {
+        let player = Player::White;
+        let mut board = Board::empty();
+        board.bar_mut(player).set(1, player);
+        board.point_mut(20).set(2, player);
+        board.rail_mut(player).set(12, player);
+        println!("{board}");
+        assert_eq!(board.pip_count(player), 25 + 2 * 4);
+    }
+
+    #[test]
+    fn eq_compares_track() {
+        assert_eq!(Board::empty(), Board::empty_on(Track::Opposite));
+        assert_ne!(Board::empty(), Board::empty_on(Track::Same));
+    }
+
+    #[test]
+    fn validate_1() {
+        assert_eq!(Board::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_2() {
+        let mut board = Board::new();
+        board.point_mut(0).set(0, Player::White);
+        board.point_mut(1).set(3, Player::None);
+        board.bar_mut(Player::Black).set(1, Player::White);
+        board.rail_mut(Player::White).set(2, Player::White);
+        println!("{board}");
+        assert_eq!(
+            board.validate(),
+            Err(Error::InvalidBoard(vec![
+                Violation::BarOwner(Player::Black, Player::White),
+                Violation::CheckerCount(Player::White, 16, CHECKERS),
+                Violation::EmptyPointOwned(1, Player::White),
+                Violation::UnownedPieces(2, 3),
+            ]))
+        );
+    }
 }
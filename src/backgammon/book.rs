@@ -0,0 +1,403 @@
+//! An opening book of recommended turns for the opening rolls and replies to
+//! them, looked up by position and roll.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::{fs, io};
+
+use crate::backgammon::{
+    Error, board::Board, compact::CompactBoard, dice_roll::DiceRoll, game::Game, notation::Turn,
+    paths, player::Player,
+};
+
+/// The first line of an opening book file.
+const HEADER: &str = "# backgammon-cli opening book";
+
+/// Turns in standard notation with their equities.
+type Lines = &'static [(&'static str, f64)];
+
+/// The lines for each of some rolls.
+type Rolls = &'static [([u8; 2], Lines)];
+
+/// The turns for each opening roll from best to worst, with their approximate
+/// equities from published rollouts.
+const OPENINGS: [([u8; 2], Lines); 15] = [
+    ([2, 1], &[("13/11 6/5", -0.01), ("24/23 13/11", -0.02)]),
+    ([3, 1], &[("8/5 6/5", 0.17)]),
+    ([3, 2], &[("24/21 13/11", -0.01), ("13/11 13/10", -0.02)]),
+    ([4, 1], &[("24/23 13/9", -0.02), ("13/9 6/5", -0.04)]),
+    ([4, 2], &[("8/4 6/4", 0.13)]),
+    (
+        [4, 3],
+        &[
+            ("24/20 13/10", -0.01),
+            ("13/10 13/9", -0.02),
+            ("24/21 13/9", -0.02),
+        ],
+    ),
+    ([5, 1], &[("24/23 13/8", -0.03), ("13/8 6/5", -0.04)]),
+    ([5, 2], &[("13/11 13/8", -0.02), ("24/22 13/8", -0.03)]),
+    ([5, 3], &[("8/3 6/3", 0.06)]),
+    ([5, 4], &[("24/20 13/8", -0.02), ("13/9 13/8", -0.03)]),
+    ([6, 1], &[("13/7 8/7", 0.12)]),
+    ([6, 2], &[("24/18 13/11", -0.01), ("13/5", -0.02)]),
+    ([6, 3], &[("24/18 13/10", -0.01), ("24/15", -0.02)]),
+    (
+        [6, 4],
+        &[("24/18 13/9", 0.0), ("8/2 6/2", -0.01), ("24/14", -0.01)],
+    ),
+    ([6, 5], &[("24/13", 0.04)]),
+];
+
+/// The usual reply with each double to an opening, used wherever it is legal
+/// after the best turn of the opening roll.
+const DOUBLE_REPLIES: [(u8, &str); 6] = [
+    (1, "8/7(2) 6/5(2)"),
+    (2, "13/11(2) 6/4(2)"),
+    (3, "8/5(2) 6/3(2)"),
+    (4, "24/20(2) 13/9(2)"),
+    (5, "13/3(2)"),
+    (6, "24/18(2) 13/7(2)"),
+];
+
+/// Replies with some of the other rolls to the best turns of the openings
+/// that make a point, with approximate equities for the player replying.
+const REPLIES: [([u8; 2], Rolls); 4] = [
+    (
+        [3, 1],
+        &[
+            ([2, 1], &[("24/23 13/11", -0.22)]),
+            ([3, 1], &[("8/5 6/5", -0.02)]),
+            ([4, 1], &[("24/23 13/9", -0.22)]),
+            ([4, 2], &[("8/4 6/4", -0.06)]),
+            ([5, 1], &[("24/23 13/8", -0.23)]),
+            ([5, 3], &[("8/3 6/3", -0.12)]),
+            ([6, 1], &[("13/7 8/7", -0.07)]),
+            ([6, 4], &[("24/14", -0.21), ("24/18 13/9", -0.22)]),
+            ([6, 5], &[("24/13", -0.13)]),
+        ],
+    ),
+    (
+        [4, 2],
+        &[
+            ([3, 1], &[("8/5 6/5", -0.01)]),
+            ([4, 1], &[("24/23 13/9", -0.19)]),
+            ([5, 2], &[("13/11 13/8", -0.18)]),
+            ([6, 1], &[("13/7 8/7", -0.04)]),
+            ([6, 4], &[("24/14", -0.17)]),
+            ([6, 5], &[("24/13", -0.10)]),
+        ],
+    ),
+    (
+        [5, 3],
+        &[
+            ([3, 1], &[("8/5 6/5", -0.03)]),
+            ([4, 2], &[("8/4 6/4", -0.07)]),
+            ([6, 1], &[("13/7 8/7", -0.05)]),
+            ([6, 4], &[("24/14", -0.13)]),
+            ([6, 5], &[("24/13", -0.08)]),
+        ],
+    ),
+    (
+        [6, 1],
+        &[
+            ([2, 1], &[("24/23 13/11", -0.26)]),
+            ([3, 1], &[("8/5 6/5", -0.04)]),
+            ([4, 2], &[("8/4 6/4", -0.08)]),
+            ([5, 3], &[("8/3 6/3", -0.15)]),
+            ([5, 4], &[("24/20 13/8", -0.24)]),
+            ([6, 4], &[("8/2 6/2", -0.22)]),
+        ],
+    ),
+];
+
+/// A turn recommended by the book.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    /// The turn in standard notation from the perspective of the player on
+    /// roll
+    pub play: String,
+    /// The equity of the turn, if known
+    pub equity: Option<f64>,
+}
+
+/// The turns recommended for positions and rolls.
+///
+/// Turns are keyed on the GNU Backgammon position ID of the board with the
+/// player on roll and the roll with the larger die first, so both players
+/// share the same lines.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Book {
+    lines: BTreeMap<(String, [u8; 2]), Vec<Line>>,
+}
+
+/// Gets the key of the current position and roll of a game.
+fn key(game: &Game) -> (String, [u8; 2]) {
+    let id = CompactBoard::from(&game.board).position_id(game.current_player);
+    let [a, b] = *game.dice_roll.dice();
+    (id, [a.max(b), a.min(b)])
+}
+
+impl Book {
+    /// Creates the built-in book of the opening rolls, the replies with
+    /// doubles to their best turns, and the replies with other rolls to the
+    /// best turns of the openings that make a point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a built-in turn is not legal.
+    #[must_use]
+    pub fn builtin() -> Self {
+        let mut book = Self::default();
+        for (roll, lines) in OPENINGS {
+            let mut game = Game::from(Player::Black, DiceRoll::from(roll), Board::new());
+            for &(play, equity) in lines {
+                let equity = Some(equity);
+                book.add(&game, play.to_owned(), equity);
+            }
+
+            let turn = game
+                .read_turn(lines[0].0)
+                .expect("opening book turn should be legal");
+            game.take_turn(&turn);
+            game.current_player = Player::White;
+            for (die, play) in DOUBLE_REPLIES {
+                game.dice_roll = DiceRoll::from([die, die]);
+                if game.read_turn(play).is_ok() {
+                    book.add(&game, play.to_owned(), None);
+                }
+            }
+
+            let replies = REPLIES.iter().filter(|(opening, _)| *opening == roll);
+            for (reply, lines) in replies.flat_map(|(_, replies)| replies.iter()) {
+                game.dice_roll = DiceRoll::from(*reply);
+                for &(play, equity) in *lines {
+                    game.read_turn(play)
+                        .expect("opening book reply should be legal");
+                    book.add(&game, play.to_owned(), Some(equity));
+                }
+            }
+        }
+        book
+    }
+
+    /// Loads the book kept in the file from `BACKGAMMON_CLI_BOOK`, or the
+    /// `backgammon-cli/book` file in the XDG data directory, falling back to
+    /// the built-in book.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or is not valid.
+    pub fn load() -> Result<Self, Error> {
+        match paths::data("BACKGAMMON_CLI_BOOK", "book") {
+            Some(path) if path.exists() => Self::read(&path),
+            _ => Ok(Self::builtin()),
+        }
+    }
+
+    /// Reads a book from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid.
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let contents =
+            fs::read_to_string(path).map_err(|error| Error::BookFailed(error.to_string()))?;
+        Self::parse(&contents)
+    }
+
+    /// Writes the book to a file in the format read by [`Book::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        let failed = |error: io::Error| Error::BookFailed(error.to_string());
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(failed)?;
+        }
+        fs::write(path, self.serialize()).map_err(failed)
+    }
+
+    /// Parses a book from lines of `<position id> <die>-<die> <equity> <turn>`,
+    /// where the equity is `-` if unknown and the turn is in standard notation
+    /// for the player on roll, ignoring blank lines and comments starting with
+    /// `#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is not valid.
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let mut book = Self::default();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidBook(line.to_owned());
+            let die = |word: &str| match word.parse::<u8>() {
+                Ok(die) if (1..=6).contains(&die) => Ok(die),
+                _ => Err(invalid()),
+            };
+
+            let mut words = line.splitn(4, char::is_whitespace).map(str::trim);
+            let (Some(id), Some(roll), Some(equity), Some(play)) =
+                (words.next(), words.next(), words.next(), words.next())
+            else {
+                return Err(invalid());
+            };
+            let Some((a, b)) = roll.split_once('-') else {
+                return Err(invalid());
+            };
+            let game = Game::from_position_id(id, [die(a)?, die(b)?]).map_err(|_| invalid())?;
+            let equity = match equity {
+                "-" => None,
+                equity => Some(equity.parse().map_err(|_| invalid())?),
+            };
+            book.add(&game, play.to_owned(), equity);
+        }
+        Ok(book)
+    }
+
+    /// Adds a turn for the current position and roll of a game, keeping the
+    /// turns with known equities from best to worst ahead of the others.
+    pub fn add(&mut self, game: &Game, play: String, equity: Option<f64>) {
+        let lines = self.lines.entry(key(game)).or_default();
+        lines.push(Line { play, equity });
+        lines.sort_by(|a, b| {
+            let equity = |line: &Line| line.equity.unwrap_or(f64::NEG_INFINITY);
+            equity(b).total_cmp(&equity(a))
+        });
+    }
+
+    /// Gets the turns recommended for the current position and roll of a
+    /// game, from best to worst.
+    #[must_use]
+    pub fn lines(&self, game: &Game) -> &[Line] {
+        self.lines.get(&key(game)).map_or(&[], Vec::as_slice)
+    }
+
+    /// Gets the best legal turn recommended for the current position and roll
+    /// of a game, if any.
+    #[must_use]
+    pub fn best_turn(&self, game: &Game) -> Option<Turn> {
+        self.lines(game)
+            .iter()
+            .find_map(|line| game.read_turn(&line.play).ok())
+    }
+
+    /// Describes the turns recommended for the current position and roll of a
+    /// game.
+    #[must_use]
+    pub fn show(&self, game: &Game) -> String {
+        let (_, [a, b]) = key(game);
+        let lines = self.lines(game);
+        if lines.is_empty() {
+            return format!("{a}-{b} is not in the book for this position");
+        }
+        let mut shown = vec![format!("Book for {a}-{b}")];
+        shown.extend(
+            lines
+                .iter()
+                .map(|line| format!("  {:<20} {}", line.play, equity(line.equity))),
+        );
+        shown.join("\n")
+    }
+
+    /// Writes the book in the format read by [`Book::parse`].
+    fn serialize(&self) -> String {
+        let mut lines = vec![HEADER.to_string()];
+        for ((id, [a, b]), book_lines) in &self.lines {
+            lines.extend(book_lines.iter().map(|line| {
+                let equity = line
+                    .equity
+                    .map_or_else(|| "-".to_string(), |equity| equity.to_string());
+                format!("{id} {a}-{b} {equity} {}", line.play)
+            }));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Formats an equity, which may not be known.
+fn equity(equity: Option<f64>) -> String {
+    equity.map_or_else(|| "-".to_string(), |equity| format!("{equity:+.3}"))
+}
+
+impl Display for Book {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Opening book")?;
+        for ((id, [a, b]), lines) in &self.lines {
+            write!(f, "\n  {id} {a}-{b}")?;
+            for line in lines {
+                write!(f, "\n    {:<20} {}", line.play, equity(line.equity))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openings() {
+        let book = Book::builtin();
+        for player in [Player::Black, Player::White] {
+            let game = Game::from(player, DiceRoll::from([1, 3]), Board::new());
+            assert_eq!(book.lines(&game)[0].play, "8/5 6/5");
+            assert_eq!(
+                book.best_turn(&game),
+                Some(game.read_turn("8/5 6/5").unwrap())
+            );
+        }
+
+        let game = Game::from(Player::Black, DiceRoll::from([6, 4]), Board::new());
+        let plays: Vec<_> = book
+            .lines(&game)
+            .iter()
+            .map(|line| line.play.as_str())
+            .collect();
+        assert_eq!(plays, ["24/18 13/9", "8/2 6/2", "24/14"]);
+    }
+
+    #[test]
+    fn replies() {
+        let book = Book::builtin();
+        let mut game = Game::from(Player::Black, DiceRoll::from([6, 1]), Board::new());
+        game.take_turn(&game.read_turn("13/7 8/7").unwrap());
+        game.current_player = Player::White;
+
+        game.dice_roll = DiceRoll::from([5, 5]);
+        assert_eq!(book.lines(&game)[0].play, "13/3(2)");
+        assert_eq!(book.lines(&game)[0].equity, None);
+        game.dice_roll = DiceRoll::from([6, 6]);
+        assert!(book.lines(&game).is_empty());
+        assert_eq!(book.best_turn(&game), None);
+
+        game.dice_roll = DiceRoll::from([1, 3]);
+        assert_eq!(book.lines(&game)[0].play, "8/5 6/5");
+        assert_eq!(book.lines(&game)[0].equity, Some(-0.04));
+        assert_eq!(
+            book.best_turn(&game),
+            Some(game.read_turn("8/5 6/5").unwrap())
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let book = Book::builtin();
+        assert_eq!(Book::parse(&book.serialize()), Ok(book));
+        assert_eq!(
+            Book::parse("4HPwATDgc/ABMA 3-7 0.1 8/5 6/5"),
+            Err(Error::InvalidBook(
+                "4HPwATDgc/ABMA 3-7 0.1 8/5 6/5".to_string()
+            ))
+        );
+    }
+}
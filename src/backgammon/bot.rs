@@ -0,0 +1,231 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use rand::{Rng, seq::IteratorRandom};
+
+use crate::backgammon::{
+    Error, analysis,
+    board::BOARD_SIZE,
+    compact::{BAR, CompactBoard},
+    dice_roll::{DiceRoll, SIDES},
+    game::Game,
+    notation::Turn,
+    player::Player,
+    win::Win,
+};
+
+/// The method a bot uses to choose which of the available turns to take.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Chooses uniformly at random between the available turns.
+    Random,
+    /// Chooses the turn resulting in the best position according to
+    /// [`evaluate`].
+    #[default]
+    Heuristic,
+}
+
+impl Strategy {
+    /// Chooses a turn for the current player of the given game. The heuristic
+    /// bot plays from the opening book of the game while the position is in
+    /// it.
+    pub(crate) fn choose_turn(self, game: &Game) -> Turn {
+        if self == Self::Heuristic
+            && let Some(turn) = game.book().and_then(|book| book.best_turn(game))
+        {
+            return turn;
+        }
+
+        let player = game.current_player;
+        let turns = game.get_available_turns();
+
+        let turn = match self {
+            Self::Random => turns.into_iter().choose(&mut rand::rng()),
+            Self::Heuristic => turns
+                .into_iter()
+                .max_by(|(_, a), (_, b)| evaluate(a, player).total_cmp(&evaluate(b, player))),
+        };
+
+        turn.map_or_else(|| Turn(vec![]), |(turn, _)| turn)
+    }
+
+    /// Chooses the doubles the current player of the given game plays after a
+    /// bonus roll.
+    ///
+    /// The heuristic bot chooses the doubles whose best turn results in the
+    /// best position.
+    pub(crate) fn choose_doubles(self, game: &Game) -> u8 {
+        let player = game.current_player;
+        let best = |die| {
+            let mut game = game.clone();
+            game.dice_roll = DiceRoll::from([die, die]);
+            game.get_available_turns()
+                .iter()
+                .map(|(_, board)| evaluate(board, player))
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+
+        match self {
+            Self::Random => rand::rng().random_range(1..=SIDES),
+            Self::Heuristic => (1..=SIDES)
+                .max_by(|&a, &b| best(a).total_cmp(&best(b)))
+                .unwrap_or(SIDES),
+        }
+    }
+
+    /// Decides whether to accept the resignation of the current player of the
+    /// given game at the offered level.
+    ///
+    /// The heuristic bot accepts when the offer is worth at least the equity of
+    /// the position, with a win counted at the level it would be if the game
+    /// ended now.
+    pub(crate) fn accepts(self, game: &Game, offered: Win) -> bool {
+        match self {
+            Self::Random => rand::random(),
+            Self::Heuristic => {
+                let player = !game.current_player;
+                let win = game.rules.limit(Win::of(&game.board, player));
+                let equity = analysis::equity(&CompactBoard::from(&game.board), player);
+                f64::from(offered.points()) >= equity * f64::from(win.points())
+            }
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "random" => Ok(Self::Random),
+            "heuristic" => Ok(Self::Heuristic),
+            _ => Err(Error::InvalidStrategy(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Random => "random",
+            Self::Heuristic => "heuristic",
+        })
+    }
+}
+
+/// Scores a board from the perspective of the given player, where higher is
+/// better. The score is measured roughly in pips, combining the race with
+/// penalties for exposed blots and bonuses for made points and primes.
+pub fn evaluate(board: &CompactBoard, player: Player) -> f64 {
+    let pieces = board.pieces(player);
+    let mut score = pips(board, !player) - pips(board, player);
+
+    // The furthest opponent piece from their perspective, which can still hit
+    // any blot it has not yet passed.
+    let furthest = board
+        .pieces(!player)
+        .iter()
+        .rposition(|&count| count > 0)
+        .unwrap_or(0);
+
+    let mut prime = 0;
+    let mut longest_prime = 0;
+
+    for (point, &count) in pieces.iter().enumerate().take(BOARD_SIZE + 1).skip(1) {
+        let location = f64::from(u8::try_from(point).expect("point should fit in u8"));
+
+        if count >= 2 {
+            score += if point <= 6 { 3.0 } else { 2.0 };
+            prime += 1;
+            longest_prime = longest_prime.max(prime);
+            continue;
+        }
+
+        prime = 0;
+
+        // A blot that the opponent can still reach is a liability, costing
+        // more the further it has travelled.
+        if count == 1 && BAR - point < furthest {
+            score -= 4.0 + (25.0 - location) / 4.0;
+        }
+    }
+
+    score + f64::from(longest_prime) * 1.5
+}
+
+/// Gets the pip count of the player as a float.
+fn pips(board: &CompactBoard, player: Player) -> f64 {
+    f64::from(u16::try_from(board.pip_count(player)).expect("pip count should fit in u16"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{
+        board::Board,
+        notation::turn,
+        notation::{Play, PositionRef},
+    };
+
+    #[test]
+    fn evaluate_symmetric() {
+        let board = CompactBoard::from(&Board::new());
+        assert!(
+            (evaluate(&board, Player::Black) - evaluate(&board, Player::White)).abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn heuristic_makes_point() {
+        let player = Player::Black;
+        let game = Game::from(player, DiceRoll::from([3, 1]), Board::new());
+        let turn = Strategy::Heuristic.choose_turn(&game);
+        assert_eq!(turn, turn!(player, (7, 4), (5, 4)));
+    }
+
+    #[test]
+    fn random_is_legal() {
+        let player = Player::White;
+        let game = Game::from(player, DiceRoll::from([6, 4]), Board::new());
+        let turn = Strategy::Random.choose_turn(&game);
+        assert_eq!(game.check_turn(&turn), Ok(()));
+    }
+
+    #[test]
+    fn heuristic_accepts_resignation() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(0).set(2, player);
+        board.rail_mut(player).set(13, player);
+        board.point_mut(3).set(15, !player);
+
+        // White cannot avoid a backgammon, so Black only accepts that.
+        let game = Game::from(!player, DiceRoll::from([6, 5]), board);
+        assert!(!Strategy::Heuristic.accepts(&game, Win::Gammon));
+        assert!(Strategy::Heuristic.accepts(&game, Win::Backgammon));
+    }
+
+    #[test]
+    fn heuristic_accepts_lopsided_race() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(12).set(15, player);
+        board.point_mut(23).set(15, !player);
+
+        // White would win the race easily, so Black takes any win offered,
+        // even though it would be a gammon if the game ended now.
+        let game = Game::from(!player, DiceRoll::from([6, 5]), board);
+        assert!(Strategy::Heuristic.accepts(&game, Win::Single));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("Random".parse(), Ok(Strategy::Random));
+        assert_eq!("heuristic".parse(), Ok(Strategy::Heuristic));
+        assert_eq!(
+            "gnubg".parse::<Strategy>(),
+            Err(Error::InvalidStrategy("gnubg".to_string()))
+        );
+    }
+}
@@ -0,0 +1,562 @@
+use itertools::Itertools;
+use std::collections::HashSet;
+
+use crate::backgammon::{
+    board::{BOARD_SIZE, Board},
+    location::{Normalized, Track},
+    notation::{Play, PositionRef, Turn},
+    player::Player,
+    rules::Rules,
+};
+
+/// The location of pieces borne off, from the perspective of their player.
+pub const OFF: usize = 0;
+
+/// The location of pieces on the bar, from the perspective of their player.
+pub const BAR: usize = BOARD_SIZE + 1;
+
+/// The last point of a player's home board.
+const HOME: usize = 6;
+
+/// A compact copy of a `Board` used for fast move generation.
+///
+/// Each player's pieces are counted by location normalized to their own
+/// perspective, so `OFF` is their rail, 1 through 24 are points, and `BAR` is
+/// their bar. A point held by one player at `i` is the opponent's `25 - i`, or
+/// their `i + 12` wrapped around the board when both move the same way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CompactBoard {
+    /// Pieces of `Player::Black` and `Player::White` respectively
+    pieces: [[u8; BOARD_SIZE + 2]; 2],
+    /// The points where a piece of `Player::Black` and `Player::White`
+    /// respectively is pinned, as bits by location from their perspective.
+    /// Pinned pieces are not counted in `pieces`.
+    pinned: [u32; 2],
+    /// How the players' paths run around the points
+    track: Track,
+}
+
+/// A single play of a piece in a `CompactBoard`, normalized to the perspective
+/// of the player moving it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompactPlay {
+    pub from: usize,
+    pub to: usize,
+}
+
+impl CompactPlay {
+    /// Converts the play to a `Play` of the given player on a board with the
+    /// given track.
+    pub fn to_play(self, player: Player, track: Track) -> Play {
+        let position = |location| match location {
+            OFF => PositionRef::Rail(player),
+            BAR => PositionRef::Bar(player),
+            point => PositionRef::Point(
+                Normalized::new(point, player)
+                    .and_then(|n| n.to_index_on(track))
+                    .expect("point should be indexable"),
+            ),
+        };
+        Play::new(player, position(self.from), position(self.to)).with_track(track)
+    }
+}
+
+/// A turn generated from a `CompactBoard` together with the board it results in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactTurn {
+    pub plays: Vec<CompactPlay>,
+    pub board: CompactBoard,
+}
+
+impl CompactTurn {
+    /// Converts the turn to a `Turn` of the given player.
+    pub fn to_turn(&self, player: Player) -> Turn {
+        let track = self.board.track;
+        Turn(
+            self.plays
+                .iter()
+                .map(|play| play.to_play(player, track))
+                .collect(),
+        )
+    }
+}
+
+impl CompactBoard {
+    /// Gets the pieces of the player by location from their perspective.
+    pub const fn pieces(&self, player: Player) -> &[u8; BOARD_SIZE + 2] {
+        &self.pieces[player as usize]
+    }
+
+    /// Gets the pieces of the player by location from their perspective,
+    /// mutably.
+    pub const fn pieces_mut(&mut self, player: Player) -> &mut [u8; BOARD_SIZE + 2] {
+        &mut self.pieces[player as usize]
+    }
+
+    /// Gets the point of the opponent that is the given point from the
+    /// perspective of the player.
+    const fn across(&self, point: usize) -> usize {
+        match self.track {
+            Track::Opposite => BAR - point,
+            Track::Same => (point + 11) % BOARD_SIZE + 1,
+        }
+    }
+
+    /// Gets the number of pieces of the opponent on a point given from the
+    /// perspective of the player.
+    const fn opponent_on(&self, player: Player, point: usize) -> u8 {
+        self.pieces[1 - player as usize][self.across(point)]
+    }
+
+    /// Checks whether a piece of the player is pinned on a point given from
+    /// their perspective.
+    pub const fn is_pinned(&self, player: Player, point: usize) -> bool {
+        self.pinned[player as usize] & (1 << point) != 0
+    }
+
+    /// Checks whether the player has a piece on a location given from their
+    /// perspective, including one pinned there.
+    const fn holds(&self, player: Player, location: usize) -> bool {
+        self.pieces(player)[location] > 0 || self.is_pinned(player, location)
+    }
+
+    /// Gets the total number of pips the player must move to bear off every
+    /// piece, including any pinned pieces.
+    pub fn pip_count(&self, player: Player) -> usize {
+        self.pieces(player)
+            .iter()
+            .enumerate()
+            .map(|(location, &count)| {
+                let pinned = usize::from(self.is_pinned(player, location));
+                location * (count as usize + pinned)
+            })
+            .sum()
+    }
+
+    /// Checks whether the players' pieces have passed each other, so neither
+    /// can hit or block the other again.
+    pub fn is_race(&self) -> bool {
+        let furthest = |player| {
+            (OFF..=BAR)
+                .rev()
+                .find(|&location| self.holds(player, location))
+                .unwrap_or(OFF)
+        };
+        let (black, white) = (furthest(Player::Black), furthest(Player::White));
+        match self.track {
+            Track::Opposite => black + white <= BAR,
+            // Paths only part once each player's pieces are all past the
+            // other's starting point.
+            Track::Same => black <= BOARD_SIZE / 2 && white <= BOARD_SIZE / 2,
+        }
+    }
+
+    /// Gets where a piece of the player would land if played from a location
+    /// with a die under the given rules, if that play is legal.
+    pub fn destination(
+        &self,
+        player: Player,
+        from: usize,
+        die: usize,
+        rules: Rules,
+    ) -> Option<usize> {
+        let pieces = self.pieces(player);
+
+        if from == OFF
+            || pieces[from] == 0
+            || (rules.enters_first() && from != BAR && pieces[BAR] > 0)
+        {
+            return None;
+        }
+
+        if from > die {
+            let to = from - die;
+            let blocked =
+                rules.is_blocked(self.opponent_on(player, to), self.is_pinned(player, to));
+            return (!blocked).then_some(to);
+        }
+
+        // Bearing off requires every piece in the home board, and a die larger
+        // than needed may only be used by the furthest piece.
+        let all_home = !(HOME + 1..=BAR).any(|location| self.holds(player, location));
+        let furthest = !(from + 1..=HOME).any(|location| self.holds(player, location));
+        (all_home && (from == die || furthest)).then_some(OFF)
+    }
+
+    /// Plays a piece of the player, sending any blot it lands on to the bar or
+    /// pinning it under the given rules, and freeing any piece it leaves
+    /// pinned on its own.
+    pub fn apply(&mut self, player: Player, play: CompactPlay, rules: Rules) {
+        let (mine, theirs) = (player as usize, 1 - player as usize);
+
+        self.pieces[mine][play.from] -= 1;
+        self.pieces[mine][play.to] += 1;
+
+        if play.from != BAR && self.pieces[mine][play.from] == 0 {
+            let from = self.across(play.from);
+            if self.is_pinned(!player, from) {
+                self.pinned[theirs] &= !(1 << from);
+                self.pieces[theirs][from] += 1;
+            }
+        }
+
+        let to = self.across(play.to);
+        if play.to != OFF && self.pieces[theirs][to] == 1 {
+            self.pieces[theirs][to] = 0;
+            if rules.pins() {
+                self.pinned[theirs] |= 1 << to;
+            } else {
+                self.pieces[theirs][BAR] += 1;
+            }
+        }
+    }
+
+    /// Generates every legal turn for the player with the given available
+    /// dice under the given rules, one for each distinct resulting board.
+    ///
+    /// A turn must use as many dice as possible and, if only one of two
+    /// different dice can be used, the larger one.
+    pub fn turns(&self, player: Player, dice: &[u8], rules: Rules) -> Vec<CompactTurn> {
+        let dice: Vec<_> = dice.iter().copied().map(usize::from).collect();
+        let doubles = dice.iter().all_equal();
+
+        let mut leaves = vec![];
+        let mut plays = vec![];
+        self.generate(player, &dice, BAR, rules, &mut plays, &mut leaves);
+        if !doubles {
+            let reversed: Vec<_> = dice.iter().copied().rev().collect();
+            self.generate(player, &reversed, BAR, rules, &mut plays, &mut leaves);
+        }
+
+        let most = leaves
+            .iter()
+            .map(|turn| turn.plays.len())
+            .max()
+            .unwrap_or(0);
+        leaves.retain(|turn| turn.plays.len() == most);
+
+        // When only one of two different dice can be used, the larger must be.
+        if most == 1 && !doubles {
+            let larger = dice.iter().copied().max().unwrap_or(0);
+            let uses = |turn: &CompactTurn| {
+                let play = turn.plays[0];
+                play.from - play.to == larger || (play.to == OFF && play.from < larger)
+            };
+            if leaves.iter().any(uses) {
+                leaves.retain(uses);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        leaves.retain(|turn| seen.insert(turn.board));
+        leaves
+    }
+
+    /// Recursively plays the remaining dice in order, pushing every sequence
+    /// that cannot be continued. With doubles, pieces are played from
+    /// non-increasing locations so each combination is only generated once.
+    fn generate(
+        &self,
+        player: Player,
+        dice: &[usize],
+        start: usize,
+        rules: Rules,
+        plays: &mut Vec<CompactPlay>,
+        leaves: &mut Vec<CompactTurn>,
+    ) {
+        let Some((&value, rest)) = dice.split_first() else {
+            leaves.push(CompactTurn {
+                plays: plays.clone(),
+                board: *self,
+            });
+            return;
+        };
+
+        let doubles = rest.first() == Some(&value);
+        let mut any = false;
+
+        for from in (1..=start).rev() {
+            let Some(to) = self.destination(player, from, value, rules) else {
+                continue;
+            };
+
+            any = true;
+            let play = CompactPlay { from, to };
+            let mut board = *self;
+            board.apply(player, play, rules);
+
+            plays.push(play);
+            let start = if doubles || rest.is_empty() {
+                from
+            } else {
+                BAR
+            };
+            board.generate(player, rest, start, rules, plays, leaves);
+            plays.pop();
+        }
+
+        if !any {
+            leaves.push(CompactTurn {
+                plays: plays.clone(),
+                board: *self,
+            });
+        }
+    }
+}
+
+impl From<&Board> for CompactBoard {
+    fn from(board: &Board) -> Self {
+        let mut compact = Self {
+            track: board.track(),
+            ..Self::default()
+        };
+
+        for player in [Player::Black, Player::White] {
+            let pieces = &mut compact.pieces[player as usize];
+            pieces[OFF] = board.rail(player).count;
+            pieces[BAR] = board.bar(player).count;
+
+            for (point, count) in pieces.iter_mut().enumerate().take(BOARD_SIZE + 1).skip(1) {
+                let index = board
+                    .index(point, player)
+                    .expect("point should be indexable");
+                let position = board.point(*index);
+                if position.player == player {
+                    *count = position.count;
+                } else if position.pinned {
+                    compact.pinned[player as usize] |= 1 << point;
+                }
+            }
+        }
+
+        compact
+    }
+}
+
+impl From<&CompactBoard> for Board {
+    fn from(compact: &CompactBoard) -> Self {
+        let mut board = Self::empty_on(compact.track);
+
+        for player in [Player::Black, Player::White] {
+            let pieces = compact.pieces(player);
+            board.rail_mut(player).set(pieces[OFF], player);
+            board.bar_mut(player).set(pieces[BAR], player);
+
+            for (point, &count) in pieces.iter().enumerate().take(BOARD_SIZE + 1).skip(1) {
+                let index = board
+                    .index(point, player)
+                    .expect("point should be indexable");
+                if count > 0 {
+                    board.point_mut(*index).set(count, player);
+                } else if compact.is_pinned(player, point) {
+                    board.point_mut(*index).pinned = true;
+                }
+            }
+        }
+
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{
+        dice_roll::DiceRoll,
+        game::Game,
+        notation::{PositionRef, turn},
+    };
+
+    /// Gets the distinct boards reached by every legal turn of a game, found
+    /// independently by trying every sequence of plays `Game::check_play`
+    /// allows and keeping those `Game::check_turn` accepts as a whole.
+    fn brute_force_boards(game: &Game) -> HashSet<CompactBoard> {
+        fn leaves(game: &Game, plays: &mut Vec<Play>, found: &mut Vec<Turn>) {
+            let available = game.get_available_plays();
+            if !game.dice_roll.any_available() || available.is_empty() {
+                found.push(Turn(plays.clone()));
+                return;
+            }
+            for play in available {
+                let mut game = game.clone();
+                game.make_play(&play);
+                plays.push(play);
+                leaves(&game, plays, found);
+                plays.pop();
+            }
+        }
+
+        let mut found = vec![];
+        leaves(game, &mut vec![], &mut found);
+
+        // The longest turns using the larger die where possible.
+        let most = found
+            .iter()
+            .map(|Turn(plays)| plays.len())
+            .max()
+            .unwrap_or(0);
+        found.retain(|Turn(plays)| plays.len() == most);
+        let smaller = game.dice_roll.iter().copied().min().unwrap_or(0);
+        let larger = |turn: &Turn| {
+            let mut after = game.clone();
+            after.take_turn(turn);
+            after.dice_roll.contains(smaller)
+        };
+        if most == 1 && game.dice_roll.iter().any(|&die| die != smaller) && found.iter().any(larger)
+        {
+            found.retain(larger);
+        }
+
+        found
+            .into_iter()
+            .map(|turn| {
+                assert_eq!(game.check_turn(&turn), Ok(()), "{turn}");
+                let mut after = game.clone();
+                after.take_turn(&turn);
+                CompactBoard::from(&after.board)
+            })
+            .collect()
+    }
+
+    /// Checks the compact generator reaches the same boards as the brute
+    /// force search of `Game` for every roll.
+    fn assert_matches_game(player: Player, board: &Board) {
+        let compact = CompactBoard::from(board);
+        for a in 1..=6 {
+            for b in a..=6 {
+                let game = Game::from(player, DiceRoll::from([a, b]), board.clone());
+                let dice: Vec<_> = game.dice_roll.iter().copied().collect();
+                let turns = compact.turns(player, &dice, Rules::Standard);
+                let boards: HashSet<_> = turns.iter().map(|turn| turn.board).collect();
+                assert_eq!(boards.len(), turns.len());
+                assert_eq!(boards, brute_force_boards(&game), "{a}-{b} for {player}");
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut board = Board::new();
+        board.point_mut(5).set(4, Player::Black);
+        board.bar_mut(Player::Black).set(1, Player::Black);
+        board.point_mut(18).set(3, Player::White);
+        board.rail_mut(Player::White).set(2, Player::White);
+
+        let compact = CompactBoard::from(&board);
+        assert_eq!(Board::from(&compact), board);
+        assert_eq!(compact.pip_count(Player::Black), 167 - 6 + 25);
+        assert_eq!(compact.pip_count(Player::White), 167 - 12);
+        for player in [Player::Black, Player::White] {
+            assert_eq!(compact.pip_count(player), board.pip_count(player));
+        }
+    }
+
+    #[test]
+    fn perspective() {
+        let compact = CompactBoard::from(&Board::new());
+        assert_eq!(compact.pieces(Player::Black), compact.pieces(Player::White));
+        assert_eq!(compact.pieces(Player::Black)[24], 2);
+        assert_eq!(compact.pieces(Player::Black)[6], 5);
+    }
+
+    #[test]
+    fn hit() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(10).set(1, player);
+        board.point_mut(7).set(1, !player);
+
+        let mut compact = CompactBoard::from(&board);
+        compact.apply(player, CompactPlay { from: 11, to: 8 }, Rules::Standard);
+
+        let mut board = Board::empty();
+        board.point_mut(7).set(1, player);
+        board.bar_mut(!player).set(1, !player);
+        assert_eq!(Board::from(&compact), board);
+    }
+
+    #[test]
+    fn race() {
+        let player = Player::Black;
+        assert!(!CompactBoard::from(&Board::new()).is_race());
+
+        let mut board = Board::empty();
+        board.point_mut(10).set(15, player);
+        board.point_mut(11).set(15, !player);
+        assert!(CompactBoard::from(&board).is_race());
+
+        board.point_mut(11).set(0, Player::None);
+        board.point_mut(9).set(15, !player);
+        assert!(!CompactBoard::from(&board).is_race());
+    }
+
+    #[test]
+    fn matches_game_opening() {
+        assert_matches_game(Player::Black, &Board::new());
+        assert_matches_game(Player::White, &Board::new());
+    }
+
+    #[test]
+    fn matches_game_bar() {
+        let player = Player::White;
+        let mut board = Board::new();
+        board.point_mut(0).set(1, player);
+        board.bar_mut(player).set(1, player);
+        board.point_mut(18).set(2, !player);
+        board.point_mut(20).set(2, !player);
+        board.point_mut(5).set(3, !player);
+        assert_matches_game(player, &board);
+    }
+
+    #[test]
+    fn opening_turns() {
+        let compact = CompactBoard::from(&Board::new());
+        let player = Player::Black;
+        let turns = compact.turns(player, &[2, 5], Rules::Standard);
+        assert_eq!(turns.len(), 8);
+
+        let board = Board::new();
+        let game = Game::from(player, DiceRoll::from([2, 5]), board);
+        for turn in turns {
+            assert_eq!(game.check_turn(&turn.to_turn(player)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn bear_off_turns() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(0).set(2, player);
+        board.point_mut(2).set(3, player);
+        board.point_mut(4).set(1, player);
+        board.point_mut(7).set(1, player);
+        board.point_mut(20).set(2, !player);
+        board.point_mut(3).set(2, !player);
+
+        let turns: Vec<_> = CompactBoard::from(&board)
+            .turns(player, &[3, 6], Rules::Standard)
+            .iter()
+            .map(|turn| turn.to_turn(player))
+            .collect();
+
+        // Bearing off from the 5 point with the 6 is legal once 8/5 is played.
+        assert!(turns.contains(&turn!(player, (7, 4), (4, off))));
+        assert!(turns.contains(&turn!(player, (7, 1), (2, off))));
+    }
+
+    #[test]
+    fn larger_die_only() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(10).set(1, player);
+        board.point_mut(5).set(2, !player);
+
+        // Either die can be played from the 11 point, but not both.
+        let turns: Vec<_> = CompactBoard::from(&board)
+            .turns(player, &[3, 2], Rules::Standard)
+            .iter()
+            .map(|turn| turn.to_turn(player))
+            .collect();
+        assert_eq!(turns, vec![turn!(player, (10, 7))]);
+    }
+}
@@ -0,0 +1,448 @@
+//! Pictures of positions for study notes, drawn as SVG images and, with the
+//! `png` feature, rasterized to PNG.
+
+use std::fs;
+use std::path::Path;
+
+use crate::backgammon::{
+    Error,
+    board::{Board, Position, STACK_HEIGHT, tables},
+    player::Player,
+};
+
+/// The thickness of the frame around the board.
+const MARGIN: i32 = 10;
+/// The space above and below the board for point numbers.
+const LABEL: i32 = 24;
+/// The space above the board for the score, when there is one.
+const HEADER: i32 = 32;
+/// The width of a point.
+const POINT: i32 = 40;
+/// The width of the bar and of the trays beside the tables.
+const SIDE: i32 = 40;
+/// The space taken by each checker in a stack.
+const CHECKER: i32 = 36;
+/// The height of each half of the board.
+const HALF: i32 = CHECKER * STACK_HEIGHT as i32;
+/// The space between the halves of the board, where the dice are thrown.
+const GAP: i32 = 40;
+/// The width of the whole image.
+const WIDTH: i32 = 2 * MARGIN + 3 * SIDE + 12 * POINT;
+
+const FRAME_COLOR: &str = "#6d4c41";
+const BED_COLOR: &str = "#efe0c0";
+const POINT_COLORS: [&str; 2] = ["#a0522d", "#d2b48c"];
+
+/// The pips of each die face, as columns and rows of a 3 by 3 grid.
+const PIPS: [&[(i32, i32)]; 6] = [
+    &[(1, 1)],
+    &[(0, 0), (2, 2)],
+    &[(0, 0), (1, 1), (2, 2)],
+    &[(0, 0), (2, 0), (0, 2), (2, 2)],
+    &[(0, 0), (2, 0), (1, 1), (0, 2), (2, 2)],
+    &[(0, 0), (2, 0), (0, 1), (2, 1), (0, 2), (2, 2)],
+];
+
+/// A picture of a position, from the perspective of a player, optionally
+/// with the dice, the doubling cube and the score.
+#[derive(Clone, Debug)]
+pub struct Diagram<'a> {
+    board: &'a Board,
+    perspective: Player,
+    dice: Option<[u8; 2]>,
+    cube: Option<(u32, Player)>,
+    score: Option<[u32; 2]>,
+    match_length: Option<u32>,
+}
+
+impl<'a> Diagram<'a> {
+    /// Creates a diagram of the board from the perspective of a player, whose
+    /// home board is drawn in the bottom right for `Player::Black` and the
+    /// bottom left for `Player::White`.
+    #[must_use]
+    pub const fn new(board: &'a Board, perspective: Player) -> Self {
+        Self {
+            board,
+            perspective,
+            dice: None,
+            cube: None,
+            score: None,
+            match_length: None,
+        }
+    }
+
+    /// Draws the dice thrown by the player the diagram is from the
+    /// perspective of.
+    #[must_use]
+    pub const fn with_dice(mut self, dice: [u8; 2]) -> Self {
+        self.dice = Some(dice);
+        self
+    }
+
+    /// Draws the doubling cube beside the board of its owner, or in the middle
+    /// when `Player::None` owns it, where a value of 1 is shown as 64.
+    #[must_use]
+    pub const fn with_cube(mut self, value: u32, owner: Player) -> Self {
+        self.cube = Some((value, owner));
+        self
+    }
+
+    /// Writes Black's and White's scores above the board.
+    #[must_use]
+    pub const fn with_score(mut self, black: u32, white: u32) -> Self {
+        self.score = Some([black, white]);
+        self
+    }
+
+    /// Writes the length of the match beside the score.
+    #[must_use]
+    pub const fn with_match_length(mut self, length: u32) -> Self {
+        self.match_length = Some(length);
+        self
+    }
+
+    /// Draws the diagram as an SVG image.
+    #[must_use]
+    pub fn to_svg(&self) -> String {
+        let frame = Frame::new(self.perspective, self.score.is_some());
+        let (width, height) = (WIDTH, frame.height);
+
+        let mut elements = vec![
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="Helvetica, Arial, DejaVu Sans, sans-serif">"#
+            ),
+            format!(r#"<rect width="{width}" height="{height}" fill="white"/>"#),
+            format!(
+                r#"<rect x="0" y="{}" width="{width}" height="{}" fill="{FRAME_COLOR}"/>"#,
+                frame.top - MARGIN,
+                frame.bottom - frame.top + 2 * MARGIN
+            ),
+        ];
+        for x in frame.tables {
+            elements.push(format!(
+                r#"<rect x="{x}" y="{}" width="{}" height="{}" fill="{BED_COLOR}"/>"#,
+                frame.top,
+                6 * POINT,
+                frame.bottom - frame.top
+            ));
+        }
+
+        if let Some([black, white]) = self.score {
+            let length = self
+                .match_length
+                .map(|length| format!(", match to {length}"))
+                .unwrap_or_default();
+            let score = format!("Black {black}, White {white}{length}");
+            elements.push(text(WIDTH / 2, HEADER - 10, 18, "black", &score));
+        }
+
+        elements.extend(self.points(&frame));
+        elements.extend(self.bar_and_trays(&frame));
+        elements.extend(self.cube_and_dice(&frame));
+        elements.push("</svg>".to_string());
+        elements.join("\n")
+    }
+
+    /// Draws the points with their numbers and the pieces on them.
+    fn points(&self, frame: &Frame) -> Vec<String> {
+        let mut elements = vec![];
+        let [top, bottom] = tables(self.perspective);
+        let halves = [
+            (top, frame.top, 1, frame.top - MARGIN - 8),
+            (bottom, frame.bottom, -1, frame.bottom + MARGIN + 18),
+        ];
+
+        for (quarters, edge, direction, label) in halves {
+            for (quarter, table) in quarters.iter().zip(frame.tables) {
+                for (&point, x) in quarter.iter().zip((table..).step_by(POINT as usize)) {
+                    let center = x + POINT / 2;
+                    let tip = edge + direction * (HALF - 10);
+                    elements.push(format!(
+                        r#"<polygon points="{x},{edge} {},{edge} {center},{tip}" fill="{}"/>"#,
+                        x + POINT,
+                        POINT_COLORS[point % 2]
+                    ));
+                    elements.push(text(center, label, 14, "black", &point.to_string()));
+
+                    if let Ok(index) = self.board.index(point, self.perspective) {
+                        let position = self.board.point(*index);
+                        elements.extend(stack(position, center, edge, direction));
+                    }
+                }
+            }
+        }
+        elements
+    }
+
+    /// Draws the pieces on the bar, waiting beside the middle of the board,
+    /// and the borne off pieces as slabs in the trays.
+    fn bar_and_trays(&self, frame: &Frame) -> Vec<String> {
+        let center = frame.bar + SIDE / 2;
+        let mut elements = stack(
+            self.board.bar(self.perspective),
+            center,
+            frame.top + HALF,
+            -1,
+        );
+        elements.extend(stack(
+            self.board.bar(!self.perspective),
+            center,
+            frame.bottom - HALF,
+            1,
+        ));
+
+        let trays = [
+            (self.board.rail(!self.perspective), frame.top, 1),
+            (self.board.rail(self.perspective), frame.bottom - 9, -1),
+        ];
+        for (position, edge, direction) in trays {
+            let (fill, stroke) = colors(position.player);
+            for slab in 0..i32::from(position.count) {
+                elements.push(format!(
+                    r#"<rect x="{}" y="{}" width="30" height="9" rx="2" fill="{fill}" stroke="{stroke}"/>"#,
+                    frame.tray + 5,
+                    edge + direction * (2 + slab * 11)
+                ));
+            }
+        }
+        elements
+    }
+
+    /// Draws the doubling cube and the dice, if there are any.
+    fn cube_and_dice(&self, frame: &Frame) -> Vec<String> {
+        let mut elements = vec![];
+        let middle = i32::midpoint(frame.top, frame.bottom);
+
+        if let Some((value, owner)) = self.cube {
+            let y = match owner {
+                Player::None => middle - 16,
+                owner if owner == self.perspective => frame.bottom - 36,
+                _ => frame.top + 4,
+            };
+            let value = if owner == Player::None && value == 1 {
+                64
+            } else {
+                value
+            };
+            elements.push(format!(
+                r#"<rect class="cube" x="{}" y="{y}" width="32" height="32" rx="4" fill="white" stroke="black" stroke-width="2"/>"#,
+                frame.cube + 4
+            ));
+            let center = frame.cube + SIDE / 2;
+            elements.push(text(center, y + 22, 16, "black", &value.to_string()));
+        }
+
+        if let Some(dice) = self.dice {
+            let center = frame.home + 3 * POINT;
+            for (die, x) in dice.into_iter().zip([center - 34, center + 6]) {
+                elements.extend(die_face(die, x, middle - 14));
+            }
+        }
+        elements
+    }
+
+    /// Draws the diagram as a PNG image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image cannot be rasterized.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        use resvg::{tiny_skia::Pixmap, usvg};
+
+        let failed = |error: &dyn std::fmt::Display| Error::DiagramFailed(error.to_string());
+
+        let mut options = usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree = usvg::Tree::from_str(&self.to_svg(), &options).map_err(|e| failed(&e))?;
+
+        let size = tree.size().to_int_size();
+        let mut pixmap = Pixmap::new(size.width(), size.height())
+            .ok_or_else(|| failed(&"the image is empty"))?;
+        resvg::render(&tree, usvg::Transform::default(), &mut pixmap.as_mut());
+        pixmap.encode_png().map_err(|e| failed(&e))
+    }
+
+    /// Saves the diagram to a file, as a PNG image if its extension is `png`
+    /// and an SVG image otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written, or a PNG image is asked
+    /// for without the `png` feature.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let png = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+
+        #[cfg(feature = "png")]
+        let contents = if png {
+            self.to_png()?
+        } else {
+            self.to_svg().into_bytes()
+        };
+        #[cfg(not(feature = "png"))]
+        let contents = if png {
+            return Err(Error::DiagramFailed(
+                "PNG images need the `png` feature".to_string(),
+            ));
+        } else {
+            self.to_svg().into_bytes()
+        };
+
+        fs::write(path, contents).map_err(|error| Error::DiagramFailed(error.to_string()))
+    }
+}
+
+/// Where the parts of a diagram are drawn.
+struct Frame {
+    /// The height of the whole image
+    height: i32,
+    /// The top edge of the top half of the board
+    top: i32,
+    /// The bottom edge of the bottom half of the board
+    bottom: i32,
+    /// The left edges of the tables, in the order they are drawn
+    tables: [i32; 2],
+    /// The left edge of the table holding the home board
+    home: i32,
+    /// The left edge of the bar
+    bar: i32,
+    /// The left edge of the tray for borne off pieces
+    tray: i32,
+    /// The left edge of the lane holding the doubling cube
+    cube: i32,
+}
+
+impl Frame {
+    fn new(perspective: Player, header: bool) -> Self {
+        let top = if header { HEADER } else { 0 } + LABEL + MARGIN;
+        let bottom = top + 2 * HALF + GAP;
+        let bar = MARGIN + SIDE + 6 * POINT;
+        let tables = [MARGIN + SIDE, bar + SIDE];
+        let lanes = [MARGIN, bar + SIDE + 6 * POINT];
+        let mirrored = perspective == Player::White;
+
+        Self {
+            height: bottom + MARGIN + LABEL,
+            top,
+            bottom,
+            tables,
+            home: tables[usize::from(!mirrored)],
+            bar,
+            tray: lanes[usize::from(!mirrored)],
+            cube: lanes[usize::from(mirrored)],
+        }
+    }
+}
+
+/// Gets the fill and outline colors of a player's checkers.
+const fn colors(player: Player) -> (&'static str, &'static str) {
+    match player {
+        Player::White => ("#fafafa", "#555555"),
+        _ => ("#1b1b1b", "#000000"),
+    }
+}
+
+/// Draws centered text.
+fn text(x: i32, y: i32, size: u32, fill: &str, content: &str) -> String {
+    format!(
+        r#"<text x="{x}" y="{y}" font-size="{size}" text-anchor="middle" fill="{fill}">{content}</text>"#
+    )
+}
+
+/// Draws the pieces of a position stacked from an edge in a direction, with
+/// the last checker of a tall stack showing how many there are.
+fn stack(position: &Position, x: i32, edge: i32, direction: i32) -> Vec<String> {
+    let pinned = u8::from(position.pinned);
+    let count = position.count + pinned;
+    let mut elements = vec![];
+
+    for row in 0..count.min(STACK_HEIGHT) {
+        // A pinned piece lies at the edge, beneath the pieces pinning it.
+        let owner = if row < pinned {
+            !position.player
+        } else {
+            position.player
+        };
+        let (fill, stroke) = colors(owner);
+        let y = edge + direction * (CHECKER / 2 + i32::from(row) * CHECKER);
+        elements.push(format!(
+            r#"<circle class="checker {}" cx="{x}" cy="{y}" r="{}" fill="{fill}" stroke="{stroke}" stroke-width="2"/>"#,
+            owner.to_string().to_lowercase(),
+            CHECKER / 2 - 1
+        ));
+        if row + 1 == STACK_HEIGHT && count > STACK_HEIGHT {
+            let (contrast, _) = colors(!position.player);
+            elements.push(text(x, y + 5, 14, contrast, &count.to_string()));
+        }
+    }
+    elements
+}
+
+/// Draws a die showing a value with its top left corner at a point.
+fn die_face(value: u8, x: i32, y: i32) -> Vec<String> {
+    let mut elements = vec![format!(
+        r#"<rect class="die" x="{x}" y="{y}" width="28" height="28" rx="4" fill="white" stroke="black" stroke-width="2"/>"#
+    )];
+    for (column, row) in PIPS[usize::from(value.clamp(1, 6)) - 1] {
+        elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="3" fill="black"/>"#,
+            x + 7 + column * 7,
+            y + 7 + row * 7
+        ));
+    }
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(svg: &str, class: &str) -> usize {
+        svg.matches(&format!(r#"class="{class}""#)).count()
+    }
+
+    #[test]
+    fn opening() {
+        let board = Board::new();
+        let svg = Diagram::new(&board, Player::Black).to_svg();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(count(&svg, "checker black"), 15);
+        assert_eq!(count(&svg, "checker white"), 15);
+        assert_eq!(count(&svg, "die"), 0);
+        assert!(!svg.contains("match to"));
+    }
+
+    #[test]
+    fn extras() {
+        let mut board = Board::empty();
+        board.point_mut(5).set(9, Player::Black);
+        board.bar_mut(Player::Black).set(6, Player::Black);
+        board.rail_mut(Player::White).set(15, Player::White);
+
+        let svg = Diagram::new(&board, Player::White)
+            .with_dice([6, 5])
+            .with_cube(1, Player::None)
+            .with_score(2, 4)
+            .with_match_length(7)
+            .to_svg();
+
+        assert_eq!(count(&svg, "checker black"), 2 * usize::from(STACK_HEIGHT));
+        assert_eq!(count(&svg, "die"), 2);
+        assert_eq!(count(&svg, "cube"), 1);
+        assert!(svg.contains(">9</text>"));
+        assert!(svg.contains(">64</text>"));
+        assert!(svg.contains("Black 2, White 4, match to 7"));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png() {
+        let board = Board::new();
+        let png = Diagram::new(&board, Player::Black).to_png().unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -77,8 +77,18 @@ impl<const N: usize> DiceRoll<N> {
             .collect()
     }
 
+    /// Gets the values the dice were rolled with
+    pub const fn dice(&self) -> &[u8; N] {
+        &self.dice
+    }
+
+    /// Gets the die values still available to be used, in ascending order
+    pub fn available(&self) -> &[u8] {
+        &self.available
+    }
+
     /// Checks if any die values are still available to be used
-    pub fn any_available(&self) -> bool {
+    pub const fn any_available(&self) -> bool {
         !self.available.is_empty()
     }
 
@@ -97,6 +107,12 @@ impl<const N: usize> DiceRoll<N> {
         }
     }
 
+    /// Makes a consumed die value available again
+    pub fn restore(&mut self, value: u8) {
+        let index = self.available.partition_point(|&d| d < value);
+        self.available.insert(index, value);
+    }
+
     /// Returns the highest available die value
     pub fn max(&self) -> u8 {
         self.available.iter().max().copied().unwrap_or(0)
@@ -106,7 +122,7 @@ impl<const N: usize> DiceRoll<N> {
 // Iterating: `iter` and `into_iter`
 
 impl<const N: usize> DiceRoll<N> {
-    pub fn iter(&self) -> std::slice::Iter<u8> {
+    pub fn iter(&self) -> std::slice::Iter<'_, u8> {
         self.available.iter()
     }
 }
@@ -189,6 +205,14 @@ mod tests {
         assert!(dice.consume(3).is_err());
     }
 
+    #[test]
+    fn test_restore_die() {
+        let mut dice = DiceRoll::from([2, 5]);
+        assert!(dice.consume(2).is_ok());
+        dice.restore(2);
+        assert_eq!(dice, DiceRoll::from([2, 5]));
+    }
+
     #[test]
     fn test_max() {
         let dice = DiceRoll::from([2, 5]);
@@ -227,7 +251,7 @@ mod tests {
     #[test]
     fn test_display() {
         let dice = DiceRoll::from([2, 5]);
-        assert_eq!(format!("{}", dice), "2-5");
-        assert_eq!(format!("{:#}", dice), "⚁-⚄");
+        assert_eq!(format!("{dice}"), "2-5");
+        assert_eq!(format!("{dice:#}"), "⚁-⚄");
     }
 }
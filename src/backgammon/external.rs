@@ -0,0 +1,266 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::backgammon::{
+    Error,
+    board::{BOARD_SIZE, Board},
+    bot::Strategy,
+    dice_roll::DiceRoll,
+    game::Game,
+    notation::Turn,
+    player::Player,
+};
+
+/// Number of `:`-separated fields in a FIBS board.
+const FIBS_FIELDS: usize = 53;
+
+/// Represents a position sent in the [FIBS board](http://www.fibs.com/fibs_interface.html#board_state)
+/// format, as used by GNU Backgammon when driving an external player.
+///
+/// The player on roll is always stored as `Player::Black` so that plays are
+/// displayed with point numbers from their perspective.
+#[derive(Debug, PartialEq)]
+pub struct FibsBoard {
+    /// The position
+    pub board: Board,
+    /// The dice of the player, if they have rolled
+    pub dice: Option<[u8; 2]>,
+    /// Whether the player has been offered the cube
+    pub was_doubled: bool,
+}
+
+impl FibsBoard {
+    /// The player the receiving side is mapped to.
+    pub const PLAYER: Player = Player::Black;
+}
+
+impl FromStr for FibsBoard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidFibsBoard(s.to_owned());
+
+        let fields: Vec<_> = s.trim().split(':').collect();
+        if fields.len() != FIBS_FIELDS || fields[0] != "board" {
+            return Err(invalid());
+        }
+
+        // Every field after the names, bar the `board` tag, is an integer.
+        let values: Vec<i32> = fields[3..]
+            .iter()
+            .map(|field| field.parse())
+            .try_collect()
+            .map_err(|_| invalid())?;
+        let field = |i: usize| values[i - 3];
+
+        let color = field(41).signum();
+        let direction = field(42);
+        if color == 0 || direction.abs() != 1 {
+            return Err(invalid());
+        }
+
+        let count = |value: i32| u8::try_from(value.unsigned_abs()).map_err(|_| invalid());
+
+        let mut board = Board::empty();
+        for i in 1..=BOARD_SIZE {
+            let value = field(6 + i);
+            if value == 0 {
+                continue;
+            }
+
+            // Normalizes the point to the receiving side moving towards 1.
+            let point = if direction < 0 { i } else { BOARD_SIZE + 1 - i };
+            let player = if value.signum() == color {
+                Self::PLAYER
+            } else {
+                !Self::PLAYER
+            };
+            board.point_mut(point - 1).set(count(value)?, player);
+        }
+
+        board
+            .rail_mut(Self::PLAYER)
+            .set(count(field(45))?, Self::PLAYER);
+        board
+            .rail_mut(!Self::PLAYER)
+            .set(count(field(46))?, !Self::PLAYER);
+        board
+            .bar_mut(Self::PLAYER)
+            .set(count(field(47))?, Self::PLAYER);
+        board
+            .bar_mut(!Self::PLAYER)
+            .set(count(field(48))?, !Self::PLAYER);
+
+        board.validate()?;
+
+        let dice = match (field(33), field(34)) {
+            (0, 0) => None,
+            (a @ 1..=6, b @ 1..=6) => Some([count(a)?, count(b)?]),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            board,
+            dice,
+            was_doubled: field(40) != 0,
+        })
+    }
+}
+
+/// Serves an external player for GNU Backgammon on the given listener,
+/// answering each connection in turn with plays chosen by the strategy.
+///
+/// Cube decisions are not evaluated, so the player never doubles and always
+/// takes.
+///
+/// A connection that cannot be accepted, read from, or written to is logged
+/// and dropped, and the next connection is served.
+pub fn serve(listener: &TcpListener, strategy: Strategy) {
+    for stream in listener.incoming() {
+        let served = stream.and_then(|stream| {
+            let reader = BufReader::new(stream.try_clone()?);
+            handle(reader, stream, strategy)
+        });
+        if let Err(error) = served {
+            eprintln!("error: {error}");
+        }
+    }
+}
+
+/// Answers every line received on the reader until it is closed.
+fn handle(reader: impl BufRead, mut writer: impl Write, strategy: Strategy) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(writer, "{}", respond(&line, strategy))?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Gets the response to a single line of the protocol.
+fn respond(line: &str, strategy: Strategy) -> String {
+    match line.parse::<FibsBoard>() {
+        Ok(FibsBoard {
+            was_doubled: true, ..
+        }) => "take".to_string(),
+        Ok(FibsBoard { dice: None, .. }) => "roll".to_string(),
+        Ok(FibsBoard {
+            board,
+            dice: Some(dice),
+            ..
+        }) => {
+            let game = Game::from(FibsBoard::PLAYER, DiceRoll::from(dice), board);
+            let Turn(plays) = strategy.choose_turn(&game);
+            plays.iter().join(" ")
+        }
+        Err(error) => format!("Error: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{Violation, board::CHECKERS, notation::Notation};
+    use std::net::TcpStream;
+    use std::thread;
+
+    /// The starting position with the receiving side to play a 5-2, as sent
+    /// by GNU Backgammon.
+    const OPENING: &str = "board:gnubg:you:7:0:0:0:-2:0:0:0:0:5:0:3:0:0:0:-5:5:0:0:0:-3:0:-5:0:0:0:0:2:0:1:5:2:0:0:1:1:1:0:1:-1:0:25:0:0:0:0:2:0:0:0";
+
+    #[test]
+    fn parse_opening() {
+        let fibs = OPENING.parse::<FibsBoard>().unwrap();
+        assert_eq!(fibs.board, Board::new());
+        assert_eq!(fibs.dice, Some([5, 2]));
+        assert!(!fibs.was_doubled);
+    }
+
+    #[test]
+    fn parse_reversed_direction() {
+        // The same position with the receiving side as X moving upwards.
+        let input = "board:gnubg:you:7:0:0:0:-2:0:0:0:0:5:0:3:0:0:0:-5:5:0:0:0:-3:0:-5:0:0:0:0:2:0:-1:5:2:0:0:1:1:1:0:-1:1:25:0:0:0:0:0:2:0:0:0";
+        let fibs = input.parse::<FibsBoard>().unwrap();
+        assert_eq!(fibs.board, Board::new());
+    }
+
+    #[test]
+    fn parse_bar_and_rail() {
+        let input = "board:gnubg:you:7:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:0:-3:-2:0:0:0:1:3:1:0:0:1:1:1:0:1:-1:0:25:13:8:2:2:0:0:0:0";
+        let fibs = input.parse::<FibsBoard>().unwrap();
+
+        let player = FibsBoard::PLAYER;
+        let mut board = Board::empty();
+        board.point_mut(20).set(3, !player);
+        board.point_mut(21).set(2, !player);
+        board.rail_mut(player).set(13, player);
+        board.rail_mut(!player).set(8, !player);
+        board.bar_mut(player).set(2, player);
+        board.bar_mut(!player).set(2, !player);
+
+        assert_eq!(fibs.board, board);
+        assert_eq!(fibs.dice, Some([3, 1]));
+    }
+
+    #[test]
+    fn bad_fibs_board() {
+        let input = "board:gnubg:you:7:0:0";
+        assert_eq!(
+            input.parse::<FibsBoard>(),
+            Err(Error::InvalidFibsBoard(input.to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_position() {
+        let input = OPENING.replace(":0:2:0:1:5:2:", ":0:3:0:1:5:2:");
+        assert_eq!(
+            input.parse::<FibsBoard>(),
+            Err(Error::InvalidBoard(vec![Violation::CheckerCount(
+                FibsBoard::PLAYER,
+                16,
+                CHECKERS
+            )]))
+        );
+    }
+
+    #[test]
+    fn respond_cube_decisions() {
+        let doubled = OPENING.replace(":1:1:1:0:1:-1:", ":2:1:1:1:1:-1:");
+        assert_eq!(respond(&doubled, Strategy::Random), "take");
+
+        let not_rolled = OPENING.replace(":1:5:2:0:0:", ":1:0:0:0:0:");
+        assert_eq!(respond(&not_rolled, Strategy::Random), "roll");
+    }
+
+    #[test]
+    fn stub_gnubg() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(&listener, Strategy::Heuristic));
+
+        // A connection sending invalid UTF-8 is dropped without ending the
+        // server.
+        let mut broken = TcpStream::connect(addr).unwrap();
+        broken.write_all(b"\xff\n").unwrap();
+        drop(broken);
+
+        let stream = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+
+        writeln!(writer, "{OPENING}").unwrap();
+        let mut reply = String::new();
+        reader.read_line(&mut reply).unwrap();
+
+        let game = Game::from(FibsBoard::PLAYER, DiceRoll::from([5, 2]), Board::new());
+        let turn = Notation::new(reply, FibsBoard::PLAYER).turn().unwrap();
+        assert_eq!(game.check_turn(&turn), Ok(()));
+    }
+}
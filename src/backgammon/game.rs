@@ -1,21 +1,64 @@
 use colored::Colorize;
+use itertools::Itertools;
 
 use crate::backgammon::{
-    Error,
+    Diagnostic, Error,
+    analysis::{Analysis, Record},
     board::{BOARD_SIZE, Board},
-    dice_roll::DiceRoll,
-    location::{Index, Normalized},
+    book::Book,
+    bot::Strategy,
+    compact::{CompactBoard, OFF},
+    diagram::Diagram,
+    dice_roll::{DiceRoll, SIDES},
+    location::Index,
     notation::{Notation, Play, PositionRef, Turn},
     player::Player,
+    rules::Rules,
+    stats::DiceStats,
+    variant::Variant,
+    win::Win,
 };
 
-use std::{collections::HashSet, io, io::Write};
+use std::{cmp::Reverse, collections::HashSet, io, io::Write, path::Path, sync::Arc};
+
+/// What `Game::make_play` changed beyond moving the piece, so the play can be
+/// taken back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Undo {
+    /// The die used by the play
+    die: u8,
+    /// Whether the play hit a blot
+    hit: bool,
+    /// Whether the play pinned a blot
+    pinned: bool,
+    /// Whether the play freed a piece it had pinned by leaving it alone
+    released: bool,
+}
+
+/// How a player finished entering their turn.
+enum Entry {
+    Played(Turn),
+    /// The player resigned and their opponent accepted, winning at this level
+    Resigned(Win),
+}
 
 #[derive(Clone)]
 pub struct Game {
     pub(crate) current_player: Player,
     pub(crate) dice_roll: DiceRoll<2>,
     pub(crate) board: Board,
+    /// The rules the game is played by
+    pub(crate) rules: Rules,
+    /// The player played by a bot and its strategy, if any
+    bot: Option<(Player, Strategy)>,
+    /// Whether turns without a choice are played without asking
+    auto_play: bool,
+    /// Whether obvious bear-offs in a race are played without asking
+    auto_bear_off: bool,
+    /// The dice rolled by each player
+    stats: DiceStats,
+    /// The opening book consulted by bots and the `book` command, if any
+    book: Option<Arc<Book>>,
 }
 
 impl Game {
@@ -25,73 +68,550 @@ impl Game {
             current_player: Player::random(),
             dice_roll: DiceRoll::opening(),
             board: Board::new(),
+            rules: Rules::Standard,
+            bot: None,
+            auto_play: true,
+            auto_bear_off: false,
+            stats: DiceStats::new(),
+            book: None,
         }
     }
 
-    #[cfg(test)]
-    const fn from(current_player: Player, dice_roll: DiceRoll<2>, board: Board) -> Self {
+    pub(crate) const fn from(current_player: Player, dice_roll: DiceRoll<2>, board: Board) -> Self {
         Self {
             current_player,
             dice_roll,
             board,
+            rules: Rules::Standard,
+            bot: None,
+            auto_play: true,
+            auto_bear_off: false,
+            stats: DiceStats::new(),
+            book: None,
         }
     }
 
-    pub fn start(&mut self) {
-        loop {
-            let saved_board = self.board.clone();
-            let saved_dice_roll = self.dice_roll.clone();
+    /// Creates a game from a GNU Backgammon position ID with `Player::Black`
+    /// on roll with the given dice.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position ID is not valid.
+    pub fn from_position_id(position_id: &str, dice: [u8; 2]) -> Result<Self, Error> {
+        let player = Player::Black;
+        let board = CompactBoard::from_position_id(position_id, player)?;
+        Ok(Self::from(
+            player,
+            DiceRoll::from(dice),
+            Board::from(&board),
+        ))
+    }
+
+    /// Lets the given strategy play for a player, such as to accept or reject
+    /// resignations.
+    #[must_use]
+    pub const fn with_bot(mut self, player: Player, strategy: Strategy) -> Self {
+        self.bot = Some((player, strategy));
+        self
+    }
+
+    /// Sets whether turns with only one possible outcome, including having no
+    /// legal moves, are played without asking. Enabled by default.
+    #[must_use]
+    pub const fn with_auto_play(mut self, auto_play: bool) -> Self {
+        self.auto_play = auto_play;
+        self
+    }
+
+    /// Sets whether a bear-off in a race is played without asking when every
+    /// die can bear off a piece in only one way. Disabled by default.
+    #[must_use]
+    pub const fn with_auto_bear_off(mut self, auto_bear_off: bool) -> Self {
+        self.auto_bear_off = auto_bear_off;
+        self
+    }
 
+    /// Counts the dice rolled during the game in the given statistics, which
+    /// are saved after every roll if they were loaded from a file.
+    #[must_use]
+    pub fn with_dice_stats(mut self, stats: DiceStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Plays the game by the given rules from their starting position.
+    #[must_use]
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self.board = rules.board();
+        self
+    }
+
+    /// Starts the game from the starting position of a variant instead of the
+    /// standard one.
+    #[must_use]
+    pub fn with_variant(mut self, variant: &Variant) -> Self {
+        self.board = variant.board();
+        self
+    }
+
+    /// Sets the opening book bots play from and the `book` command shows.
+    #[must_use]
+    pub fn with_book(mut self, book: Arc<Book>) -> Self {
+        self.book = Some(book);
+        self
+    }
+
+    /// Gets the opening book of the game, if any.
+    pub(crate) fn book(&self) -> Option<&Book> {
+        self.book.as_deref()
+    }
+
+    /// Plays the game until it is won or a resignation is accepted, returning
+    /// the winner and the level they won at, or nothing if input ends first.
+    pub fn start(&mut self) -> Option<(Player, Win)> {
+        let start = self.clone();
+        let mut history = vec![];
+        // The first dice may have been chosen or rerolled to avoid doubles, so
+        // only dice rolled later are counted in the statistics.
+        let mut rolled = false;
+        // Whether the dice are doubles chosen after a bonus roll
+        let mut chosen = false;
+
+        let result = loop {
             println!("\n{self}\n");
 
-            let notation = match self.get_notation() {
-                Ok(notation) => notation,
-                Err(error) => {
-                    println!("{}", error.to_string().red().bold());
-                    continue;
+            let player = self.current_player;
+            let dice = *self.dice_roll.dice();
+            let on_bar = self.board.bar(player).count;
+            let automatic = self
+                .bot_of(player)
+                .map(|strategy| strategy.choose_turn(self))
+                .or_else(|| self.automatic_turn());
+
+            let turn = if let Some(turn) = automatic {
+                let Turn(plays) = &turn;
+                if plays.is_empty() {
+                    println!("{player} has no legal moves, passing");
+                } else {
+                    println!("{player} plays {}", turn.notation(&self.board));
+                }
+                self.take_turn(&turn);
+                turn
+            } else {
+                match self.enter_turn() {
+                    Ok(Entry::Played(turn)) => turn,
+                    Ok(Entry::Resigned(win)) => break (!player, win),
+                    Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return None,
+                    Err(error) => {
+                        println!("{}", error.to_string().red().bold());
+                        continue;
+                    }
                 }
             };
+            let Turn(plays) = &turn;
+            let moves = plays.len();
+            history.push(Record { player, dice, turn });
+
+            if rolled && !chosen {
+                self.record_stats(player, dice, on_bar);
+            }
+
+            if self.board.all_in_rail(player) {
+                println!(
+                    "{player} won {}-{}",
+                    self.board.rail(player).count,
+                    self.board.rail(!player).count,
+                );
+                break (player, self.rules.limit(Win::of(&self.board, player)));
+            }
+
+            if self.rules.pins_mother(&self.board, player) {
+                println!("{player} pinned the mother piece of {}", !player);
+                break (player, Win::Gammon);
+            }
+
+            if chosen {
+                chosen = false;
+                self.dice_roll = DiceRoll::new();
+                println!("{player} rolls again");
+            } else if self.rules.is_bonus(dice) && moves == 2 {
+                let doubles = loop {
+                    match self.choose_doubles() {
+                        Ok(doubles) => break doubles,
+                        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return None,
+                        Err(error) => println!("{}", error.to_string().red().bold()),
+                    }
+                };
+                println!("{player} chooses {doubles}-{doubles}");
+                self.dice_roll = DiceRoll::from([doubles; 2]);
+                chosen = true;
+            } else {
+                self.change_turn();
+            }
+            rolled = true;
+        };
+
+        println!("\n{}", Analysis::new(&start, &history));
+        Some(result)
+    }
 
-            let turn = match notation.turn() {
-                Ok(turn) => turn,
-                Err(error) => {
-                    println!("{}", error.to_string().red().bold());
+    /// Counts a turn the player has just played in the dice statistics, with
+    /// the number of their pieces that were on the bar before it.
+    fn record_stats(&mut self, player: Player, dice: [u8; 2], on_bar: u8) {
+        self.stats.record_roll(player, dice);
+        // Pieces waiting to enter at the start were never hit, so only count
+        // entering under rules where pieces start on the board and must enter
+        // first.
+        if on_bar > 0 && self.rules.enters_first() {
+            self.stats
+                .record_bar(player, self.board.bar(player).count < on_bar);
+        }
+        if let Err(error) = self.stats.save() {
+            println!("{}", error.to_string().red().bold());
+        }
+    }
+
+    /// Reads plays from the current player, making each as it is entered,
+    /// until their turn is complete. A whole turn entered at once is played
+    /// immediately, otherwise `done` finishes the turn and `back` takes back
+    /// the last play.
+    fn enter_turn(&mut self) -> io::Result<Entry> {
+        let mut partial = PartialTurn::new(self);
+
+        loop {
+            let input = self.get_input(!partial.is_empty())?;
+
+            let result = match input.trim() {
+                "back" => {
+                    if !partial.back(self) {
+                        println!("{}", "there are no plays to take back".red().bold());
+                    }
+                    Ok(false)
+                }
+                "done" => partial.done().map(|_| true),
+                "stats" => {
+                    println!("\n{}\n", self.stats);
+                    Ok(false)
+                }
+                "book" => {
+                    match self.book() {
+                        Some(book) => println!("\n{}\n", book.show(self)),
+                        None => println!("{}", "there is no opening book".red().bold()),
+                    }
+                    Ok(false)
+                }
+                input if input.starts_with("diagram ") => {
+                    let path = input.trim_start_matches("diagram").trim();
+                    self.diagram().save(Path::new(path)).map(|()| {
+                        println!("saved diagram to {path}");
+                        false
+                    })
+                }
+                input if input.starts_with("resign") => {
+                    partial.clear(self);
+                    if let Some(win) = self.resign(input.trim_start_matches("resign").trim())? {
+                        return Ok(Entry::Resigned(win));
+                    }
                     continue;
                 }
+                _ => Notation::new(input, self.current_player)
+                    .with_track(self.board.track())
+                    .turn()
+                    .and_then(|turn| partial.enter(self, turn)),
             };
 
-            if let Err(error) = self.check_turn(&turn) {
+            match result {
+                Ok(true) => return Ok(Entry::Played(partial.turn())),
+                Ok(false) => {}
+                Err(error) => println!("{}", error.to_string().red().bold()),
+            }
+
+            if !partial.is_empty() {
+                println!("\n{self}\n");
+            }
+        }
+    }
+
+    /// Gets the turn to play without asking the current player, if there is
+    /// no choice to make or it is an obvious bear-off.
+    fn automatic_turn(&self) -> Option<Turn> {
+        let player = self.current_player;
+        let mut turns = self.get_available_turns();
+
+        let board = CompactBoard::from(&self.board);
+        if self.auto_bear_off && board.is_race() {
+            let off = |board: &CompactBoard| usize::from(board.pieces(player)[OFF]);
+            let before = off(&board);
+            let every_die = |(Turn(plays), board): &(Turn, CompactBoard)| {
+                !plays.is_empty() && off(board) - before == plays.len()
+            };
+
+            if let [turn] = turns
+                .iter()
+                .filter(|turn| every_die(turn))
+                .collect::<Vec<_>>()[..]
+            {
+                return Some(turn.0.clone());
+            }
+        }
+
+        match turns.len() {
+            1 if self.auto_play => turns.pop().map(|(turn, _)| turn),
+            _ => None,
+        }
+    }
+
+    /// Asks the current player which doubles to play after playing a bonus
+    /// roll.
+    fn choose_doubles(&self) -> io::Result<u8> {
+        if let Some(strategy) = self.bot_of(self.current_player) {
+            return Ok(strategy.choose_doubles(self));
+        }
+
+        let prompt = format!("{}, choose doubles to play (1-6): ", self.current_player);
+        loop {
+            match read_line(&prompt)?.trim().parse::<u8>() {
+                Ok(die) if (1..=SIDES).contains(&die) => return Ok(die),
+                _ => println!("{}", "doubles must be from 1 to 6".red().bold()),
+            }
+        }
+    }
+
+    /// Draws the position as seen by the player to play, with their dice.
+    #[must_use]
+    pub const fn diagram(&self) -> Diagram<'_> {
+        Diagram::new(&self.board, self.current_player).with_dice(*self.dice_roll.dice())
+    }
+
+    /// Gets the strategy playing for the player, if they are a bot.
+    fn bot_of(&self, player: Player) -> Option<Strategy> {
+        self.bot
+            .filter(|(bot, _)| *bot == player)
+            .map(|(_, strategy)| strategy)
+    }
+
+    /// Prompts the current player for input, showing the dice still to be
+    /// played once part of the turn has been entered.
+    fn get_input(&self, partial: bool) -> io::Result<String> {
+        let dice = &self.dice_roll;
+        let prompt = match dice.available() {
+            _ if !partial => format!("{} to play ({dice}): ", self.current_player),
+            [] => format!("{} to play ({dice}, done or back): ", self.current_player),
+            left => format!(
+                "{} to play ({dice}, {} left): ",
+                self.current_player,
+                left.iter().join("-")
+            ),
+        };
+        read_line(&prompt)
+    }
+
+    /// Offers the resignation of the current player at the given level to
+    /// their opponent, returning the level of the opponent's win if the game
+    /// ended.
+    fn resign(&self, level: &str) -> io::Result<Option<Win>> {
+        let offered = match level.parse::<Win>() {
+            Ok(offered) => offered,
+            Err(error) => {
                 println!("{}", error.to_string().red().bold());
-                self.board = saved_board;
-                self.dice_roll = saved_dice_roll;
-                continue;
+                return Ok(None);
             }
+        };
+
+        let opponent = !self.current_player;
+        let accepted = match self.bot_of(opponent) {
+            Some(strategy) => strategy.accepts(self, offered),
+            None => loop {
+                let prompt = format!("{opponent}, accept resignation of a {offered}? (y/n): ");
+                match read_line(&prompt)?.trim().to_lowercase().as_str() {
+                    "y" | "yes" => break true,
+                    "n" | "no" => break false,
+                    _ => {}
+                }
+            },
+        };
+
+        if accepted {
+            let points = offered.points();
+            let unit = if points == 1 { "point" } else { "points" };
+            println!(
+                "{} resigned, {opponent} wins a {offered} ({points} {unit})",
+                self.current_player,
+            );
+        } else {
+            println!("{opponent} rejected the resignation");
+        }
+        Ok(accepted.then_some(offered))
+    }
+
+    /// Reads a whole turn of the current player from standard notation,
+    /// checking that it is legal.
+    pub(crate) fn read_turn(&self, notation: &str) -> Result<Turn, Error> {
+        let turn = Notation::new(notation.to_owned(), self.current_player)
+            .with_track(self.board.track())
+            .turn()?;
+        let mut game = self.clone();
+        let mut partial = PartialTurn::new(&game);
+        partial.enter(&mut game, turn)?;
+        partial.done()
+    }
 
-            self.take_turn(&turn);
+    /// Expands a play into the legal plays through its intermediate points,
+    /// which is just the play itself if it uses a single die. Plays reaching
+    /// different positions depending on the intermediate points, such as when
+    /// only one path hits, are ambiguous.
+    fn expand_play(&self, play: &Play) -> Result<Vec<Play>, Error> {
+        let error = match self.check_play(play) {
+            Ok(()) => return Ok(vec![play.clone()]),
+            Err(error @ Error::InvalidPlayLength(_)) => error,
+            Err(error) => return Err(error),
+        };
+
+        let mut paths = self.paths(play);
+        let mut seen = HashSet::new();
+        paths.retain(|(_, after)| seen.insert(CompactBoard::from(&after.board)));
+
+        match paths.len() {
+            0 => Err(error),
+            1 => Ok(paths.pop().map(|(path, _)| path).unwrap_or_default()),
+            _ => Err(Error::AmbiguousPlay {
+                play: play.to_string(),
+                options: paths
+                    .into_iter()
+                    .map(|(path, _)| Turn(path).notation(&self.board))
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Explains why a play, entered at the given position of a turn counting
+    /// from one, cannot be made, with facts about the board and legal plays
+    /// close to it.
+    fn diagnose(&self, index: usize, play: &Play, error: Error) -> Error {
+        if matches!(error, Error::AmbiguousPlay { .. }) {
+            return error;
+        }
+
+        let player = self.current_player;
+        let describe = |position: &PositionRef| -> String {
+            match *position {
+                PositionRef::Bar(_) => "the bar".to_string(),
+                PositionRef::Rail(_) => "the rail".to_string(),
+                PositionRef::Point(_) => format!("point {}", self.board.locate(position, player)),
+            }
+        };
+        let pieces = |count: u8| match count {
+            1 => "1 piece".to_string(),
+            count => format!("{count} pieces"),
+        };
+        let held = |position: &PositionRef| {
+            let held = self.board.get(position);
+            match held.count {
+                0 => format!("{} is empty", describe(position)),
+                count => format!(
+                    "{} is held by {} with {}",
+                    describe(position),
+                    held.player,
+                    pieces(count)
+                ),
+            }
+        };
 
-            if self.board.all_in_rail(self.current_player) {
-                break;
+        let mut facts = vec![];
+        match error {
+            Error::PlayMadeOntoOpposingPiece => facts.push(held(&play.to)),
+            Error::PlayMadeFromEmptyPoint | Error::PlayMadeWithOpposingPiece => {
+                facts.push(held(&play.from));
+            }
+            Error::PlayMadeWithBarFilled => {
+                let count = self.board.bar(player).count;
+                facts.push(format!("{player} has {} on the bar", pieces(count)));
+            }
+            Error::InvalidBearOff => {
+                let outside: u8 = CompactBoard::from(&self.board).pieces(player)[7..]
+                    .iter()
+                    .sum();
+                facts.push(format!(
+                    "{player} has {} outside their home board",
+                    pieces(outside)
+                ));
+            }
+            Error::InvalidPlayDirection => {
+                facts.push(format!("{player} moves towards their home board"));
             }
-            self.change_turn();
+            _ => {}
         }
-        println!(
-            "{} won {}-{}",
-            self.current_player,
-            self.board.rail(self.current_player).count,
-            self.board.rail(!self.current_player).count,
-        );
+
+        let remaining = self.dice_roll.available();
+        if remaining.is_empty() {
+            facts.push("no dice remaining".to_string());
+        } else {
+            facts.push(format!("dice remaining: {}", remaining.iter().join("-")));
+        }
+
+        // Suggest the legal plays sharing an end with the play, or any legal
+        // play if none do.
+        let available: Vec<_> = self
+            .get_available_plays()
+            .into_iter()
+            .sorted_by_key(ToString::to_string)
+            .collect();
+        let close: Vec<_> = available
+            .iter()
+            .filter(|other| other.from == play.from || other.to == play.to)
+            .collect();
+        let suggestions = if close.is_empty() {
+            available.iter().take(3).map(ToString::to_string).collect()
+        } else {
+            close.iter().take(3).map(ToString::to_string).collect()
+        };
+
+        Error::IllegalPlay(Diagnostic {
+            index,
+            play: play.to_string(),
+            error: Box::new(error),
+            facts,
+            suggestions,
+        })
     }
 
-    #[allow(unstable_name_collisions)]
-    fn get_notation(&self) -> io::Result<Notation> {
-        let prompt = format!("{} to play ({}): ", self.current_player, self.dice_roll);
-        print!("{}", prompt.green().italic());
-        io::stdout().flush()?;
+    /// Gets every way to make a play as a sequence of legal plays each using
+    /// a single die, along with the game after it.
+    fn paths(&self, play: &Play) -> Vec<(Vec<Play>, Self)> {
+        if self.check_play(play).is_ok() {
+            let mut game = self.clone();
+            game.make_play(play);
+            return vec![(vec![play.clone()], game)];
+        }
+
+        let player = self.current_player;
+        let (from, to) = (
+            self.board.locate(&play.from, player),
+            self.board.locate(&play.to, player),
+        );
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        let mut paths = vec![];
+        for &die in self.dice_roll.iter().dedup() {
+            let Some(step) = from.checked_sub(usize::from(die)).filter(|&step| step > to) else {
+                continue;
+            };
+
+            let step = self.board.position_ref(step, player);
+            let first = Play::new(player, play.from, step).with_track(self.board.track());
+            if self.check_play(&first).is_err() {
+                continue;
+            }
 
-        Ok(Notation::new(input, self.current_player))
+            let mut game = self.clone();
+            game.make_play(&first);
+            let rest = Play::new(player, first.to, play.to).with_track(self.board.track());
+            for (mut path, after) in game.paths(&rest) {
+                path.insert(0, first.clone());
+                paths.push((path, after));
+            }
+        }
+        paths
     }
 
     pub(crate) fn check_turn(&self, turn: &Turn) -> Result<(), Error> {
@@ -107,7 +627,10 @@ impl Game {
             return Err(Error::IncompleteTurn);
         }
 
-        if !self.get_available_turns().contains(turn) {
+        // Turns are compared by the board they result in, so any order of the
+        // same plays is accepted.
+        let board = CompactBoard::from(&game.board);
+        if !self.get_available_turns().iter().any(|(_, b)| *b == board) {
             return Err(Error::NonMaximalTurn);
         }
 
@@ -128,8 +651,12 @@ impl Game {
             return Err(Error::PlayMadeOutOfTurn);
         }
 
-        // Ensure that if there is a piece in the bar it is played.
-        if self.board.bar(play.player).count > 0 && !matches!(play.from, PositionRef::Bar(_)) {
+        // Ensure that if there is a piece in the bar it is played, unless the
+        // rules let pieces on the board move first.
+        if self.rules.enters_first()
+            && self.board.bar(play.player).count > 0
+            && !matches!(play.from, PositionRef::Bar(_))
+        {
             return Err(Error::PlayMadeWithBarFilled);
         }
 
@@ -166,24 +693,27 @@ impl Game {
             return Err(Error::InvalidPlayDirection);
         }
 
-        // Ensure that a piece is only played onto another player's piece if the other player's piece the only piece on that space.
-        if to.player == !play.player && to.count > 1 {
+        // Ensure that a piece is only played onto another player's pieces if
+        // the rules let it hit or pin them.
+        if to.player == !play.player && self.rules.is_blocked(to.count, to.pinned) {
             return Err(Error::PlayMadeOntoOpposingPiece);
         }
 
         // Ensure play is possible from the dice rolls.
-        let len = to.distance(from).try_into().expect("value was truncated");
+        let len = play
+            .distance(&self.board)
+            .try_into()
+            .expect("value was truncated");
         if !self.dice_roll.contains(len) {
             // Ensure a piece can be borne off with a greater roll than necessary only if there are no pieces behind it.
-            let index = from
-                .location
-                .to_index()
-                .expect("location should be indexable");
-
-            if !matches!(play.to, PositionRef::Rail(_))
-                || self.board.any_behind(*index, play.player)
-                || len > self.dice_roll.max()
-            {
+            // Pieces entering from the bar never bear off.
+            let bears_off = matches!(play.to, PositionRef::Rail(_))
+                && matches!(play.from, PositionRef::Point(_))
+                && !self
+                    .board
+                    .any_behind(self.board.locate(&play.from, play.player), play.player);
+
+            if !bears_off || len > self.dice_roll.max() {
                 return Err(Error::InvalidPlayLength(len));
             }
         }
@@ -191,41 +721,47 @@ impl Game {
         Ok(())
     }
 
-    pub(super) fn make_play(&mut self, play: &Play) {
-        // let board_borrow = &self.board;
-        let from = self.board.get(&play.from);
-        let to = self.board.get(&play.to);
+    /// Makes a play checked by `check_play`, returning what is needed to take
+    /// it back with `undo_play`.
+    pub(super) fn make_play(&mut self, play: &Play) -> Undo {
+        let valid = cfg!(debug_assertions) && self.board.validate().is_ok();
 
         // Remove possible play from the dice rolls ensuring that the proper die
         // is removed if a piece was borne off with a greater than necessary roll.
-        let len = to.distance(from).try_into().expect("value was truncated");
+        let len = play
+            .distance(&self.board)
+            .try_into()
+            .expect("value was truncated");
 
+        let mut die = len;
         if self.dice_roll.consume(len).is_err() {
             // Ensure a piece can be borne off with a greater roll than necessary
             // only if there are no pieces behind it.
 
             assert!(matches!(play.to, PositionRef::Rail(_)));
 
-            let index = from
-                .location
-                .to_index()
-                .expect("location should be indexable");
+            let from = self.board.locate(&play.from, play.player);
+            assert!(!self.board.any_behind(from, play.player));
 
-            assert!(!self.board.any_behind(*index, play.player));
-
-            let max = self.dice_roll.max();
-            self.dice_roll.consume(max).expect("invalid play length");
+            die = self.dice_roll.max();
+            self.dice_roll.consume(die).expect("invalid play length");
         }
 
-        // If there is a blot where the player is moving to, then remove it and
-        // send it to their bar.
-        if to.player == !play.player && to.count == 1 {
+        // If there is a blot where the player is moving to, then either pin it
+        // or remove it and send it to their bar.
+        let to = self.board.get(&play.to);
+        let blot = to.player == !play.player && to.count == 1;
+        let pinned = blot && self.rules.pins();
+        let hit = blot && !pinned;
+        if blot {
             let player = to.player;
             let to = self.board.get_mut(&play.to);
             to.count = 0;
             to.player = Player::None;
-            let bar = self.board.bar_mut(player);
-            bar.count += 1;
+            to.pinned = pinned;
+            if hit {
+                self.board.bar_mut(player).count += 1;
+            }
         }
 
         // Make the play.
@@ -237,12 +773,57 @@ impl Game {
         to.count += 1;
 
         // Reset the player of the previous position if it is empty and not from
-        // the bar
-        let from = self.board.get(&play.from);
-        if from.count == 0 && !matches!(play.from, PositionRef::Bar(_)) {
-            let from = self.board.get_mut(&play.from);
+        // the bar, handing it to any piece pinned there.
+        let from = self.board.get_mut(&play.from);
+        let released = from.count == 0 && from.pinned;
+        if released {
+            from.pinned = false;
+            from.set(1, !play.player);
+        } else if from.count == 0 && !matches!(play.from, PositionRef::Bar(_)) {
             from.player = Player::None;
         }
+
+        // Ensure a play from a valid board leaves it valid. Tests often use
+        // partial positions, which are not checked.
+        if valid && let Err(error) = self.board.validate() {
+            panic!("play {play} broke the board: {error}");
+        }
+
+        Undo {
+            die,
+            hit,
+            pinned,
+            released,
+        }
+    }
+
+    /// Takes back a play made with `make_play`, restoring any piece it hit
+    /// and the die it used.
+    pub(super) fn undo_play(&mut self, play: &Play, undo: Undo) {
+        let to = self.board.get_mut(&play.to);
+        to.count -= 1;
+        if to.count == 0 && matches!(play.to, PositionRef::Point(_)) {
+            to.player = Player::None;
+        }
+
+        if undo.hit || undo.pinned {
+            let to = self.board.get_mut(&play.to);
+            to.set(1, !play.player);
+            to.pinned = false;
+        }
+        if undo.hit {
+            self.board.bar_mut(!play.player).count -= 1;
+        }
+
+        let from = self.board.get_mut(&play.from);
+        if undo.released {
+            from.count = 0;
+            from.pinned = true;
+        }
+        from.player = play.player;
+        from.count += 1;
+
+        self.dice_roll.restore(undo.die);
     }
 
     fn change_turn(&mut self) {
@@ -250,39 +831,37 @@ impl Game {
         self.current_player.switch();
     }
 
-    fn get_available_plays(&self) -> HashSet<Play> {
-        fn board_iter(board: &Board, player: Player) -> Box<dyn Iterator<Item = PositionRef> + '_> {
-            if board.bar(player).count > 0 {
-                Box::new(std::iter::once(PositionRef::Bar(player)))
-            } else {
-                Box::new(
-                    (0..BOARD_SIZE)
-                        .map(|i| PositionRef::Point(Index::try_from(i).unwrap()))
-                        .filter(move |p| board.get(p).player == player),
-                )
+    /// Gets every legal single play for the current player with the remaining
+    /// dice.
+    #[must_use]
+    pub fn get_available_plays(&self) -> HashSet<Play> {
+        fn board_iter(
+            board: &Board,
+            player: Player,
+            rules: Rules,
+        ) -> Box<dyn Iterator<Item = PositionRef> + '_> {
+            let points = (0..BOARD_SIZE)
+                .map(|i| PositionRef::Point(Index::try_from(i).unwrap()))
+                .filter(move |p| board.get(p).player == player);
+            match board.bar(player).count {
+                0 => Box::new(points),
+                _ if rules.enters_first() => Box::new(std::iter::once(PositionRef::Bar(player))),
+                _ => Box::new(std::iter::once(PositionRef::Bar(player)).chain(points)),
             }
         }
 
-        board_iter(&self.board, self.current_player)
+        board_iter(&self.board, self.current_player, self.rules)
             .flat_map(move |board_position| {
                 self.dice_roll
                     .iter()
                     .flat_map(|&roll| {
                         let player = self.current_player;
                         let from = board_position;
-                        let from_location =
-                            self.board.get(&board_position).location.normalize(player);
-
-                        let to_location = match from_location.checked_sub(roll as usize) {
-                            Some(i) => Normalized::new(i, player)?,
-                            None => Normalized::new(0, player)?,
-                        };
-
-                        let to = to_location
-                            .to_index()
-                            .map_or(PositionRef::Rail(player), PositionRef::Point);
+                        let from_location = self.board.locate(&from, player);
+                        let to_location = from_location.saturating_sub(usize::from(roll));
+                        let to = self.board.position_ref(to_location, player);
 
-                        let play = Play::new(player, from, to);
+                        let play = Play::new(player, from, to).with_track(self.board.track());
 
                         self.check_play(&play)?;
 
@@ -293,47 +872,127 @@ impl Game {
             .collect()
     }
 
-    fn get_available_turns(&self) -> HashSet<Turn> {
-        fn get_turns_recursive(game: &Game) -> Vec<Vec<Play>> {
-            let plays = game.get_available_plays();
-            if plays.is_empty() {
-                return vec![vec![]];
+    /// Gets every legal turn for the current player along with the board it
+    /// results in. Each distinct resulting board appears once, reached by its
+    /// plays in canonical order.
+    #[must_use]
+    pub fn get_available_turns(&self) -> Vec<(Turn, CompactBoard)> {
+        let player = self.current_player;
+        let dice: Vec<_> = self.dice_roll.iter().copied().collect();
+
+        CompactBoard::from(&self.board)
+            .turns(player, &dice, self.rules)
+            .into_iter()
+            .map(|turn| (self.canonicalize(turn.to_turn(player)), turn.board))
+            .collect()
+    }
+
+    /// Orders the plays of a legal turn from the furthest piece to the nearest,
+    /// playing larger moves first, as long as that order is still legal.
+    fn canonicalize(&self, turn: Turn) -> Turn {
+        let Turn(mut plays) = turn.clone();
+        let location = |position: &PositionRef| self.board.locate(position, self.current_player);
+        plays.sort_by_key(|play| (Reverse(location(&play.from)), location(&play.to)));
+
+        let mut game = self.clone();
+        let legal = plays.iter().all(|play| {
+            let legal = game.check_play(play).is_ok();
+            if legal {
+                game.make_play(play);
             }
+            legal
+        });
+
+        if legal { Turn(plays) } else { turn }
+    }
+}
 
-            plays
-                .into_iter()
-                .flat_map(|play| {
-                    let mut game = game.clone();
-                    game.make_play(&play);
-                    let plays: Vec<Vec<Play>> = get_turns_recursive(&game)
-                        .into_iter()
-                        .map(|mut next_plays| {
-                            next_plays.splice(0..0, [play.clone()]);
-                            next_plays
-                        })
-                        .collect();
-                    plays
-                })
-                .collect()
+/// A turn being entered a play at a time, with each play made on the game as
+/// it is entered.
+struct PartialTurn {
+    /// The game before the turn, used to check the turn as a whole
+    start: Game,
+    /// The plays made so far and what is needed to take each back
+    made: Vec<(Play, Undo)>,
+}
+
+impl PartialTurn {
+    fn new(game: &Game) -> Self {
+        Self {
+            start: game.clone(),
+            made: vec![],
         }
-        let turns: HashSet<Turn> = get_turns_recursive(&self.clone())
-            .into_iter()
-            .map(Turn)
-            .collect();
+    }
 
-        let max = turns
-            .iter()
-            .map(|turn| turn.distance(&self.board))
-            .max()
-            .unwrap_or(0);
+    const fn is_empty(&self) -> bool {
+        self.made.is_empty()
+    }
 
-        turns
-            .into_iter()
-            .filter(|turn| turn.distance(&self.board) == max)
-            .collect()
+    /// Gets the plays made so far as a turn.
+    fn turn(&self) -> Turn {
+        Turn(self.made.iter().map(|(play, _)| play.clone()).collect())
+    }
+
+    /// Checks and makes each play of the entered turn in order, expanding
+    /// plays using more than one die and stopping at the first that is not
+    /// legal. Returns whether the turn is complete, which is only when a whole
+    /// turn is entered at once. Plays made stay made even if the whole turn is
+    /// not legal, so they can be taken back.
+    fn enter(&mut self, game: &mut Game, turn: Turn) -> Result<bool, Error> {
+        let whole = self.is_empty();
+        let Turn(plays) = turn;
+
+        for (index, play) in plays.iter().enumerate() {
+            let path = game
+                .expand_play(play)
+                .map_err(|error| game.diagnose(index + 1, play, error))?;
+            for play in path {
+                let undo = game.make_play(&play);
+                self.made.push((play, undo));
+            }
+        }
+
+        let complete = !game.dice_roll.any_available() || game.get_available_plays().is_empty();
+        if whole && complete {
+            self.done().map(|_| true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Takes back the last play made, returning whether there was one.
+    fn back(&mut self, game: &mut Game) -> bool {
+        self.made.pop().is_some_and(|(play, undo)| {
+            game.undo_play(&play, undo);
+            true
+        })
+    }
+
+    /// Takes back every play made.
+    fn clear(&mut self, game: &mut Game) {
+        while self.back(game) {}
+    }
+
+    /// Checks that the plays made so far form a complete, legal turn.
+    fn done(&self) -> Result<Turn, Error> {
+        let turn = self.turn();
+        self.start.check_turn(&turn)?;
+        Ok(turn)
     }
 }
 
+/// Prompts for and reads a line of standard input.
+pub fn read_line(prompt: &str) -> io::Result<String> {
+    print!("{}", prompt.green().italic());
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(input)
+}
+
 impl std::fmt::Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.current_player {
@@ -1159,28 +1818,18 @@ mod test {
         let board = Board::new();
         let game = Game::from(player, DiceRoll::from([2, 5]), board);
         let turns = HashSet::from([
-            turn!(player, (5, 3), (7, 2)),
-            turn!(player, (5, 3), (12, 7)),
-            turn!(player, (7, 2), (5, 3)),
-            turn!(player, (7, 2), (7, 5)),
-            turn!(player, (7, 2), (12, 10)),
-            turn!(player, (7, 2), (23, 21)),
-            turn!(player, (7, 5), (7, 2)),
-            turn!(player, (7, 5), (12, 7)),
-            turn!(player, (12, 10), (7, 2)),
+            turn!(player, (12, 7), (12, 10)),
             turn!(player, (12, 10), (10, 5)),
-            turn!(player, (12, 10), (12, 7)),
             turn!(player, (12, 7), (5, 3)),
-            turn!(player, (12, 7), (7, 5)),
-            turn!(player, (12, 7), (12, 10)),
-            turn!(player, (12, 7), (23, 21)),
+            turn!(player, (12, 10), (7, 2)),
             turn!(player, (23, 21), (12, 7)),
             turn!(player, (23, 21), (7, 2)),
-            turn!(player, (23, 21), (12, 7)),
+            turn!(player, (7, 2), (7, 5)),
+            turn!(player, (7, 2), (5, 3)),
         ]);
 
         println!("{game}");
-        assert_eq!(turns, game.get_available_turns());
+        assert_eq!(turns, available_turns(&game));
     }
 
     #[test]
@@ -1199,7 +1848,7 @@ mod test {
         let turns = HashSet::from([turn!(player)]);
 
         println!("{game}");
-        assert_eq!(turns, game.get_available_turns());
+        assert_eq!(turns, available_turns(&game));
     }
 
     #[test]
@@ -1232,6 +1881,320 @@ mod test {
         let turns = HashSet::from([turn!(player, (10, 15), (15, 18))]);
 
         println!("{game}");
-        assert_eq!(turns, game.get_available_turns());
+        assert_eq!(turns, available_turns(&game));
+    }
+
+    #[test]
+    fn get_available_turns_4() {
+        let player = Player::Black;
+        let board = Board::new();
+        let game = Game::from(player, DiceRoll::from([3, 1]), board);
+
+        println!("{game}");
+        let turns = available_turns(&game);
+        assert_eq!(turns.len(), 16);
+        assert!(turns.contains(&turn!(player, (7, 4), (5, 4))));
+        assert!(!turns.contains(&turn!(player, (5, 4), (7, 4))));
+    }
+
+    #[test]
+    fn get_available_turns_5() {
+        let player = Player::White;
+        let board = Board::new();
+        let game = Game::from(player, DiceRoll::from([2, 2]), board);
+
+        println!("{game}");
+        let boards: HashSet<_> = game
+            .get_available_turns()
+            .into_iter()
+            .map(|(turn, board)| {
+                let mut game = game.clone();
+                game.take_turn(&turn);
+                assert_eq!(CompactBoard::from(&game.board), board);
+                board
+            })
+            .collect();
+        assert_eq!(boards.len(), game.get_available_turns().len());
+    }
+
+    #[test]
+    fn transposed_turn() {
+        let player = Player::Black;
+        let board = Board::new();
+        let game = Game::from(player, DiceRoll::from([3, 1]), board);
+        let turn = turn!(player, (5, 4), (7, 4));
+
+        println!("{game}");
+        assert_eq!(game.check_turn(&turn), Ok(()));
+    }
+
+    #[test]
+    fn bear_off_4() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(0).set(2, player);
+        board.point_mut(2).set(3, player);
+        board.point_mut(4).set(1, player);
+        board.point_mut(7).set(1, player);
+
+        let game = Game::from(player, DiceRoll::from([3, 6]), board);
+        let turn = turn!(player, (7, 4), (4, off));
+
+        println!("{game}");
+        assert_eq!(game.check_turn(&turn), Ok(()));
+    }
+
+    #[test]
+    fn automatic_pass() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.bar_mut(player).set(1, player);
+        board.point_mut(5).set(4, player);
+        for index in 18..24 {
+            board.point_mut(index).set(2, !player);
+        }
+
+        let game = Game::from(player, DiceRoll::from([3, 5]), board);
+        assert_eq!(game.automatic_turn(), Some(Turn(vec![])));
+        assert_eq!(game.with_auto_play(false).automatic_turn(), None);
+    }
+
+    #[test]
+    fn automatic_forced_turn() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(10).set(1, player);
+        board.point_mut(5).set(2, !player);
+
+        let game = Game::from(player, DiceRoll::from([3, 2]), board);
+        assert_eq!(game.automatic_turn(), Some(turn!(player, (10, 7))));
+    }
+
+    #[test]
+    fn automatic_bear_off() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.point_mut(0).set(2, player);
+        board.point_mut(2).set(3, player);
+        board.point_mut(4).set(1, player);
+        board.point_mut(20).set(2, !player);
+
+        let game = Game::from(player, DiceRoll::from([5, 3]), board.clone());
+        assert_eq!(game.automatic_turn(), None);
+        assert_eq!(
+            game.with_auto_bear_off(true).automatic_turn(),
+            Some(turn!(player, (4, off), (2, off)))
+        );
+
+        // With a 2, nothing can be borne off from the 3 point.
+        let game = Game::from(player, DiceRoll::from([5, 2]), board);
+        assert_eq!(game.with_auto_bear_off(true).automatic_turn(), None);
+    }
+
+    #[test]
+    fn bar_stats() {
+        let player = Player::Black;
+        let mut board = Board::new();
+        board.point_mut(23).set(1, player);
+        board.bar_mut(player).set(1, player);
+
+        // A hit piece entering is counted.
+        let mut game = Game::from(player, DiceRoll::from([3, 1]), board);
+        game.take_turn(&game.read_turn("bar/22 6/5").unwrap());
+        game.record_stats(player, [3, 1], 1);
+        let mut expected = DiceStats::new();
+        expected.record_roll(player, [3, 1]);
+        expected.record_bar(player, true);
+        assert_eq!(game.stats, expected);
+
+        // Pieces entering at the start of acey-deucey are not.
+        let mut game =
+            Game::from(player, DiceRoll::from([3, 1]), Board::new()).with_rules(Rules::AceyDeucey);
+        let on_bar = game.board.bar(player).count;
+        game.take_turn(&game.read_turn("bar/22 bar/24").unwrap());
+        game.record_stats(player, [3, 1], on_bar);
+        let mut expected = DiceStats::new();
+        expected.record_roll(player, [3, 1]);
+        assert_eq!(game.stats, expected);
+    }
+
+    #[test]
+    fn partial_turn() {
+        let player = Player::Black;
+        let mut game = Game::from(player, DiceRoll::from([3, 1]), Board::new());
+        let start = game.clone();
+        let mut partial = PartialTurn::new(&game);
+
+        assert_eq!(partial.enter(&mut game, turn!(player, (7, 4))), Ok(false));
+        assert_eq!(game.dice_roll.available(), [1]);
+        assert_eq!(partial.done(), Err(Error::IncompleteTurn));
+
+        // A mistake leaves the plays before it made.
+        assert!(matches!(
+            partial.enter(&mut game, turn!(player, (5, 6))),
+            Err(Error::IllegalPlay(Diagnostic { error, .. })) if *error == Error::InvalidPlayDirection
+        ));
+        assert_eq!(partial.enter(&mut game, turn!(player, (5, 4))), Ok(false));
+        assert_eq!(partial.done(), Ok(turn!(player, (7, 4), (5, 4))));
+
+        assert!(partial.back(&mut game));
+        assert!(partial.back(&mut game));
+        assert!(!partial.back(&mut game));
+        assert_eq!(game.board, start.board);
+        assert_eq!(game.dice_roll, start.dice_roll);
+    }
+
+    #[test]
+    fn partial_turn_whole() {
+        let player = Player::Black;
+        let mut game = Game::from(player, DiceRoll::from([3, 1]), Board::new());
+        let mut partial = PartialTurn::new(&game);
+        let turn = turn!(player, (7, 4), (5, 4));
+        assert_eq!(partial.enter(&mut game, turn), Ok(true));
+
+        // Playing the smaller die when the larger could be played is made,
+        // but not accepted.
+        let mut board = Board::empty();
+        board.point_mut(10).set(1, player);
+        board.point_mut(5).set(2, !player);
+        let mut game = Game::from(player, DiceRoll::from([3, 2]), board);
+        let mut partial = PartialTurn::new(&game);
+        assert_eq!(
+            partial.enter(&mut game, turn!(player, (10, 8))),
+            Err(Error::NonMaximalTurn)
+        );
+        assert!(partial.back(&mut game));
+        assert_eq!(partial.enter(&mut game, turn!(player, (10, 7))), Ok(true));
+    }
+
+    #[test]
+    fn expand_combined_play() {
+        let player = Player::Black;
+        let game = Game::from(player, DiceRoll::from([3, 2]), Board::new());
+        let turn = expand(&game, &turn!(player, (12, 7))).unwrap();
+        assert_eq!(game.check_turn(&turn), Ok(()));
+        assert_eq!(turn.0.len(), 2);
+
+        // Only 13/11/8 is open when White holds the 10 point.
+        let mut board = Board::new();
+        board.point_mut(9).set(2, !player);
+        board.point_mut(18).set(3, !player);
+        let game = Game::from(player, DiceRoll::from([3, 2]), board);
+        assert_eq!(
+            expand(&game, &turn!(player, (12, 7))),
+            Ok(turn!(player, (12, 10), (10, 7)))
+        );
+    }
+
+    #[test]
+    fn expand_doubles() {
+        let player = Player::Black;
+        let game = Game::from(player, DiceRoll::from([3, 3]), Board::new());
+        assert_eq!(
+            expand(&game, &turn!(player, (12, 3), (7, 4))),
+            Ok(turn!(player, (12, 9), (9, 6), (6, 3), (7, 4)))
+        );
+    }
+
+    #[test]
+    fn expand_ambiguous() {
+        let player = Player::Black;
+        let mut board = Board::new();
+        board.point_mut(9).set(1, !player);
+        board.point_mut(18).set(4, !player);
+        let game = Game::from(player, DiceRoll::from([3, 2]), board);
+
+        assert_eq!(
+            expand(&game, &turn!(player, (12, 7))),
+            Err(Error::AmbiguousPlay {
+                play: "13/8".to_string(),
+                options: vec!["13/11 11/8".to_string(), "13/10* 10/8".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn expand_blocked() {
+        let player = Player::Black;
+        let mut board = Board::new();
+        board.point_mut(9).set(2, !player);
+        board.point_mut(10).set(2, !player);
+        board.point_mut(18).set(1, !player);
+        let game = Game::from(player, DiceRoll::from([3, 2]), board);
+        assert_eq!(
+            expand(&game, &turn!(player, (12, 7))),
+            Err(Error::InvalidPlayLength(5))
+        );
+    }
+
+    #[test]
+    fn diagnose_blocked_point() {
+        let player = Player::Black;
+        let mut game = Game::from(player, DiceRoll::from([5, 5]), Board::new());
+        let mut partial = PartialTurn::new(&game);
+
+        let error = partial
+            .enter(&mut game, turn!(player, (12, 7), (23, 18)))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::IllegalPlay(Diagnostic {
+                index: 2,
+                play: "24/19".to_string(),
+                error: Box::new(Error::PlayMadeOntoOpposingPiece),
+                facts: vec![
+                    "point 19 is held by White with 5 pieces".to_string(),
+                    "dice remaining: 5-5-5".to_string(),
+                ],
+                suggestions: vec!["13/8".to_string(), "8/3".to_string()],
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "play 2 (24/19): attempted to illegally play onto another player\n  \
+             point 19 is held by White with 5 pieces\n  \
+             dice remaining: 5-5-5\n  \
+             legal plays include 13/8, 8/3"
+        );
+    }
+
+    #[test]
+    fn diagnose_bar() {
+        let player = Player::White;
+        let mut board = Board::new();
+        board.point_mut(0).set(1, !player);
+        board.bar_mut(!player).set(0, !player);
+        board.bar_mut(player).set(1, player);
+        board.point_mut(18).set(4, player);
+        let mut game = Game::from(player, DiceRoll::from([6, 1]), board);
+
+        let Err(Error::IllegalPlay(diagnostic)) =
+            PartialTurn::new(&game).enter(&mut game, turn!(player, (16, 22)))
+        else {
+            panic!("expected an illegal play");
+        };
+        assert_eq!(*diagnostic.error, Error::PlayMadeWithBarFilled);
+        assert_eq!(diagnostic.facts[0], "White has 1 piece on the bar");
+        assert_eq!(diagnostic.suggestions, vec!["bar/24"]);
+    }
+
+    fn expand(game: &Game, turn: &Turn) -> Result<Turn, Error> {
+        let mut game = game.clone();
+        let mut expanded = vec![];
+        for play in &turn.0 {
+            let path = game.expand_play(play)?;
+            for play in &path {
+                game.make_play(play);
+            }
+            expanded.extend(path);
+        }
+        Ok(Turn(expanded))
+    }
+
+    fn available_turns(game: &Game) -> HashSet<Turn> {
+        game.get_available_turns()
+            .into_iter()
+            .map(|(turn, _)| turn)
+            .collect()
     }
 }
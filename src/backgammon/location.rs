@@ -1,8 +1,21 @@
-use crate::backgammon::{board::BOARD_SIZE, player::Player, Error};
+use crate::backgammon::{Error, board::BOARD_SIZE, player::Player};
 use std::convert::From;
 use std::fmt::Display;
 use std::ops::Deref;
 
+/// How the paths of the two players around the board run, which decides
+/// where each player's points are.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Track {
+    /// The players move in opposite directions, so a player's 1 point is their
+    /// opponent's 24 point.
+    #[default]
+    Opposite,
+    /// Both players move in the same direction from opposite corners of the
+    /// board, so a player's 1 point is their opponent's 13 point.
+    Same,
+}
+
 /// Represents a range from 0 to 25 where 0 is the player's rail and the opponent's
 /// bar, 1 is the player's ace, 24 is the opponent's ace, and 25 is the player's
 /// bar and the opponent's rail. This position is normalized to a given player's
@@ -37,15 +50,24 @@ impl Normalized {
     /// Converts the given `NormalizedLocation` to an `IndexLocation` from a
     /// `Player` perspective.
     pub fn to_index(&self) -> Result<Index, Error> {
+        self.to_index_on(Track::Opposite)
+    }
+
+    /// Converts the given `NormalizedLocation` to an `IndexLocation` from a
+    /// `Player` perspective on a board with the given track.
+    pub fn to_index_on(&self, track: Track) -> Result<Index, Error> {
         // Here to avoid underflow errors from unsigned subtraction.
         if self.0 == 0 || self.0 == (BOARD_SIZE + 1) {
             return Err(Error::InvalidIndexLocation(self.0));
         }
 
-        match self.1 {
-            Player::Black => (self.0 - 1).try_into(),
-            Player::White => ((BOARD_SIZE + 1) - self.0 - 1).try_into(),
-            Player::None => panic!("cannot convert to index with perspective of `Player::None`"),
+        match (self.1, track) {
+            (Player::Black, _) => (self.0 - 1).try_into(),
+            (Player::White, Track::Opposite) => ((BOARD_SIZE + 1) - self.0 - 1).try_into(),
+            (Player::White, Track::Same) => ((self.0 + 11) % BOARD_SIZE).try_into(),
+            (Player::None, _) => {
+                panic!("cannot convert to index with perspective of `Player::None`")
+            }
         }
     }
 }
@@ -164,10 +186,21 @@ impl Index {
     /// be instantiated from `usize`, thus when this method is called, the
     /// `NormalizedLocation` is guaranteed to be in range `0..=25`.
     pub fn normalize(self, perspective: Player) -> Normalized {
-        match perspective {
-            Player::Black => Normalized::new(self.0 + 1, perspective),
-            Player::White => Normalized::new((BOARD_SIZE + 1) - (self.0 + 1), perspective),
-            Player::None => panic!("cannot normalize with perspective of `Player::None`"),
+        self.normalize_on(perspective, Track::Opposite)
+    }
+
+    /// Normalize the given `IndexLocation` to a `Player` perspective on a
+    /// board with the given track.
+    pub fn normalize_on(self, perspective: Player, track: Track) -> Normalized {
+        match (perspective, track) {
+            (Player::Black, _) => Normalized::new(self.0 + 1, perspective),
+            (Player::White, Track::Opposite) => {
+                Normalized::new((BOARD_SIZE + 1) - (self.0 + 1), perspective)
+            }
+            (Player::White, Track::Same) => {
+                Normalized::new((self.0 + 12) % BOARD_SIZE + 1, perspective)
+            }
+            (Player::None, _) => panic!("cannot normalize with perspective of `Player::None`"),
         }
         .unwrap()
     }
@@ -373,4 +406,24 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn same_track() -> Result<(), Error> {
+        let white = |point| Normalized::new(point, Player::White);
+        assert_eq!(
+            white(24)?.to_index_on(Track::Same),
+            Ok(Index::try_from(11)?)
+        );
+        assert_eq!(white(13)?.to_index_on(Track::Same), Ok(Index::try_from(0)?));
+        assert_eq!(
+            white(12)?.to_index_on(Track::Same),
+            Ok(Index::try_from(23)?)
+        );
+        for index in 0..BOARD_SIZE {
+            let index = Index::try_from(index)?;
+            let normalized = index.normalize_on(Player::White, Track::Same);
+            assert_eq!(normalized.to_index_on(Track::Same), Ok(index));
+        }
+        Ok(())
+    }
 }
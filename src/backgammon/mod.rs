@@ -1,13 +1,48 @@
+mod analysis;
 mod board;
+mod book;
+mod bot;
+mod compact;
+mod diagram;
 mod dice_roll;
+mod external;
 mod game;
 mod location;
 mod notation;
+mod paths;
+mod perft;
 mod player;
+mod position_id;
+mod profiles;
+#[cfg(test)]
+mod properties;
+mod rules;
+mod setup;
+mod stats;
+mod theme;
+mod tournament;
+mod training;
+mod variant;
+mod win;
 
+pub use analysis::{Analysis, Decision, Flag, Record};
+pub use book::{Book, Line};
+pub use bot::Strategy;
+pub use diagram::Diagram;
+pub use external::serve;
 pub use game::Game;
+pub use perft::{divide, perft};
+pub use player::Player;
+pub use profiles::{Profile, Profiles};
+pub use rules::{Rules, TAVLI};
+pub use setup::Setup;
+pub use stats::{DiceStats, Tally};
+pub use theme::{Borders, Layout, Palette, Theme};
+pub use tournament::{Format, Pairing, Tournament};
+pub use training::{Answer, Category, Drill, Progress, Question, Score, train};
+pub use variant::Variant;
+pub use win::Win;
 
-use player::Player;
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Eq, Error)]
@@ -21,9 +56,132 @@ pub enum Error {
     #[error("cannot create `IndexLocation` from `{0}`")]
     InvalidIndexLocation(usize),
 
+    #[error("strategy '{0}' is not valid")]
+    InvalidStrategy(String),
+
+    #[error("fibs board '{0}' is not valid")]
+    InvalidFibsBoard(String),
+
+    #[error("position id '{0}' is not valid")]
+    InvalidPositionId(String),
+
+    #[error("player '{0}' is not valid")]
+    InvalidPlayer(String),
+
+    #[error("win level '{0}' is not valid")]
+    InvalidWin(String),
+
+    #[error("diagram could not be saved: {0}")]
+    DiagramFailed(String),
+
+    #[error("borders '{0}' are not valid")]
+    InvalidBorders(String),
+
+    #[error("config '{0}' is not valid")]
+    InvalidConfig(String),
+
+    #[error("layout '{0}' is not valid")]
+    InvalidLayout(String),
+
+    #[error("palette '{0}' is not valid")]
+    InvalidPalette(String),
+
+    #[error("profile name '{0}' is not valid")]
+    InvalidProfileName(String),
+
+    #[error("profiles line '{0}' is not valid")]
+    InvalidProfiles(String),
+
+    #[error("profiles could not be used: {0}")]
+    ProfilesFailed(String),
+
+    #[error("profile '{0}' does not exist")]
+    UnknownProfile(String),
+
+    #[error("tournament format '{0}' is not valid")]
+    InvalidFormat(String),
+
+    #[error("tournament line '{0}' is not valid")]
+    InvalidTournament(String),
+
+    #[error("tournament could not be used: {0}")]
+    TournamentFailed(String),
+
+    #[error("no tournament has been created")]
+    NoTournament,
+
+    #[error("a tournament needs at least two players and a match length")]
+    TooFewPlayers,
+
+    #[error("player '{0}' is already registered")]
+    DuplicatePlayer(String),
+
+    #[error("players cannot be registered after the first round")]
+    TournamentStarted,
+
+    #[error("round {0} still has matches to play")]
+    PendingMatches(usize),
+
+    #[error("the tournament is over")]
+    TournamentOver,
+
+    #[error("there is no match between '{0}' and '{1}' left to play")]
+    UnknownMatch(String, String),
+
+    #[error("opening book line '{0}' is not valid")]
+    InvalidBook(String),
+
+    #[error("opening book could not be used: {0}")]
+    BookFailed(String),
+
+    #[error("drill '{0}' is not valid")]
+    InvalidDrill(String),
+
+    #[error("drill category '{0}' is not valid")]
+    InvalidCategory(String),
+
+    #[error("cube action '{0}' is not one of the answers")]
+    InvalidCubeAction(String),
+
+    #[error("training progress line '{0}' is not valid")]
+    InvalidProgress(String),
+
+    #[error("training could not be used: {0}")]
+    TrainingFailed(String),
+
+    #[error("rules '{0}' are not valid")]
+    InvalidRules(String),
+
+    #[error("variant '{0}' is not valid")]
+    InvalidVariant(String),
+
+    #[error("dice statistics line '{0}' is not valid")]
+    InvalidStats(String),
+
+    #[error("dice statistics could not be used: {0}")]
+    StatsFailed(String),
+
+    #[error("setup command '{0}' is not valid")]
+    InvalidSetupCommand(String),
+
+    #[error(
+        "board is not valid: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    InvalidBoard(Vec<Violation>),
+
+    #[error("{0} has already borne off every piece")]
+    GameAlreadyWon(Player),
+
     #[error("notation '{0}' is not valid")]
     InvalidNotation(String),
 
+    #[error("play '{play}' is ambiguous, it could be {}", options.join(" or "))]
+    AmbiguousPlay { play: String, options: Vec<String> },
+
+    #[error("{0}")]
+    IllegalPlay(Diagnostic),
+
     #[error("play of length '{0}' is not valid")]
     InvalidPlayLength(u8),
 
@@ -62,3 +220,51 @@ pub enum Error {
     )]
     NonMaximalTurn,
 }
+
+/// A broken invariant of a `Board`. Points are numbered from the perspective
+/// of `Player::Black`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Violation {
+    #[error("{0} has {1} pieces but must have {2}")]
+    CheckerCount(Player, u32, u32),
+
+    #[error("point {0} is empty but belongs to {1}")]
+    EmptyPointOwned(usize, Player),
+
+    #[error("point {0} has {1} pieces but belongs to no one")]
+    UnownedPieces(usize, u8),
+
+    #[error("the bar of {0} holds pieces of {1}")]
+    BarOwner(Player, Player),
+
+    #[error("the rail of {0} holds pieces of {1}")]
+    RailOwner(Player, Player),
+}
+
+/// An illegal play together with the context needed to understand and fix it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The position of the play in the entered turn, counting from one
+    pub index: usize,
+    /// The play in notation
+    pub play: String,
+    /// Why the play is illegal
+    pub error: Box<Error>,
+    /// Facts about the board relevant to the error
+    pub facts: Vec<String>,
+    /// Legal plays close to the one entered
+    pub suggestions: Vec<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "play {} ({}): {}", self.index, self.play, self.error)?;
+        for fact in &self.facts {
+            write!(f, "\n  {fact}")?;
+        }
+        if !self.suggestions.is_empty() {
+            write!(f, "\n  legal plays include {}", self.suggestions.join(", "))?;
+        }
+        Ok(())
+    }
+}
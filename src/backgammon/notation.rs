@@ -1,14 +1,14 @@
+use std::collections::HashSet;
 use std::fmt::Display;
-use std::string::ToString;
 
 use itertools::Itertools;
 use regex::Regex;
 
 use crate::backgammon::{
+    Error,
     board::Board,
-    location::{Index, Normalized},
+    location::{Index, Normalized, Track},
     player::Player,
-    Error,
 };
 
 /// Represents [backgammon notation](https://en.wikipedia.org/wiki/Backgammon_notation)
@@ -18,18 +18,36 @@ pub struct Notation {
     input: String,
     /// The player
     player: Player,
+    /// The track the player moves along, which decides where their points are
+    track: Track,
 }
 
 impl Notation {
     /// Create a `Notation`.
     pub const fn new(input: String, player: Player) -> Self {
-        Self { input, player }
+        Self {
+            input,
+            player,
+            track: Track::Opposite,
+        }
+    }
+
+    /// Reads points as on a board with the given track.
+    #[must_use]
+    pub const fn with_track(mut self, track: Track) -> Self {
+        self.track = track;
+        self
     }
 
     /// Tries to generate a `Turn` from itself.
+    ///
+    /// Each whitespace-separated group is a chain of locations such as
+    /// `24/18/13`, where any point may be marked with `*` for a hit and the
+    /// chain may end with `(n)` to play it `n` times, as in `13/7*(2)`.
     pub fn turn(&self) -> Result<Turn, Error> {
-        let re = Regex::new(r"^((\d+)|(bar))(/\d+)*(/((\d+)|(off)))$")
-            .expect("regex should always be valid");
+        let re =
+            Regex::new(r"^(((\d+)|(bar))(/\d+\*?)*(/((\d+\*?)|(off))))(\((?<times>[1-4])\))?$")
+                .expect("regex should always be valid");
 
         // Get all play groups, the plays of each whitespace-seperated group of
         // invididual simple notations.
@@ -37,12 +55,19 @@ impl Notation {
             .input
             .split_whitespace()
             .map(|group| {
-                re.find(group).map_or_else(
+                re.captures(group).map_or_else(
                     || Err(Error::InvalidNotation(group.to_owned())),
-                    |m| {
-                        let input = m.as_str().to_owned();
-                        let notation = Self::new(input, self.player);
-                        notation.get_play_group()
+                    |captures| {
+                        let times = captures
+                            .name("times")
+                            .map_or(1, |m| m.as_str().parse().expect("times should be a digit"));
+                        let input = captures[1].replace('*', "");
+                        let plays = Self::new(input, self.player)
+                            .with_track(self.track)
+                            .get_play_group()?;
+                        Ok(std::iter::repeat_n(plays, times)
+                            .flatten()
+                            .collect::<Vec<_>>())
                     },
                 )
             })
@@ -58,7 +83,7 @@ impl Notation {
         let plays = spaces
             .into_iter()
             .tuple_windows()
-            .map(|(from, to)| Play::new(self.player, from, to))
+            .map(|(from, to)| Play::new(self.player, from, to).with_track(self.track))
             .collect();
         Ok(plays)
     }
@@ -74,7 +99,7 @@ impl Notation {
                     pos => {
                         let pos = pos.parse::<usize>().expect("pos should be an integer");
                         let norm = Normalized::new(pos, self.player)?;
-                        let index = norm.to_index()?;
+                        let index = norm.to_index_on(self.track)?;
                         PositionRef::Point(index)
                     }
                 })
@@ -83,36 +108,69 @@ impl Notation {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Turn(pub Vec<Play>);
 
 impl Turn {
+    /// Gets the total number of pips moved by the plays of the turn.
     pub fn distance(&self, board: &Board) -> usize {
         let Self(plays) = self;
-        plays
-            .iter()
-            .map(|play| {
-                let from = board.get(&play.from);
-                let to = board.get(&play.to);
-                from.distance(to)
-            })
-            .sum()
+        plays.iter().map(|play| play.distance(board)).sum()
     }
 }
 
-#[allow(unstable_name_collisions)]
+impl Turn {
+    /// Formats the turn in standard notation given the board before it,
+    /// marking plays that hit a blot with `*`.
+    pub fn notation(&self, board: &Board) -> String {
+        let Self(plays) = self;
+        let mut landed = HashSet::new();
+
+        let plays = plays.iter().map(|play| {
+            let to = board.get(&play.to);
+            let hit = matches!(play.to, PositionRef::Point(_))
+                && to.player == !play.player
+                && to.count == 1
+                && !landed.contains(&play.to);
+            landed.insert(play.to);
+            (play, hit)
+        });
+
+        group(plays)
+    }
+}
+
+/// Formats plays in standard notation, grouping repeated plays as `(n)`.
+fn group<'a>(plays: impl Iterator<Item = (&'a Play, bool)>) -> String {
+    let mut groups: Vec<(&Play, bool, usize)> = vec![];
+    for (play, hit) in plays {
+        match groups.iter_mut().find(|(other, _, _)| *other == play) {
+            Some((_, hits, count)) => {
+                *hits |= hit;
+                *count += 1;
+            }
+            None => groups.push((play, hit, 1)),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(play, hit, count)| {
+            let hit = if hit { "*" } else { "" };
+            match count {
+                1 => format!("{play}{hit}"),
+                count => format!("{play}{hit}({count})"),
+            }
+        })
+        .join(" ")
+}
+
+/// Formats the turn in standard notation, grouping repeated plays as `(n)`.
+/// Hits are only marked by [`Turn::notation`], which knows the board.
 impl Display for Turn {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self(plays) = self;
-        write!(
-            f,
-            "Turn({})",
-            plays
-                .iter()
-                .map(ToString::to_string)
-                .intersperse(", ".to_string())
-                .collect::<String>()
-        )
+        f.write_str(&group(plays.iter().map(|play| (play, false))))
     }
 }
 
@@ -147,22 +205,40 @@ pub struct Play {
     pub player: Player,
     pub from: PositionRef,
     pub to: PositionRef,
+    /// The track the player moves along, which decides how points are named
+    pub track: Track,
 }
 
 impl Play {
     pub const fn new(player: Player, from: PositionRef, to: PositionRef) -> Self {
-        Self { player, from, to }
+        Self {
+            player,
+            from,
+            to,
+            track: Track::Opposite,
+        }
+    }
+
+    /// Names the points of the play as on a board with the given track.
+    #[must_use]
+    pub const fn with_track(mut self, track: Track) -> Self {
+        self.track = track;
+        self
     }
 
     pub fn is_valid_direction(&self, board: &Board) -> bool {
-        let from = board.get(&self.from);
-        let to = board.get(&self.to);
+        assert!(
+            self.player != Player::None,
+            "There is no move direction for `Player::None`."
+        );
+        board.locate(&self.to, self.player) < board.locate(&self.from, self.player)
+    }
 
-        match self.player {
-            Player::White => to.location > from.location,
-            Player::Black => to.location < from.location,
-            Player::None => panic!("There is no move direction for `Player::None`."),
-        }
+    /// Gets the number of pips the play moves its piece.
+    pub fn distance(&self, board: &Board) -> usize {
+        board
+            .locate(&self.from, self.player)
+            .abs_diff(board.locate(&self.to, self.player))
     }
 }
 
@@ -174,12 +250,14 @@ impl std::fmt::Display for Play {
             match self.from {
                 PositionRef::Bar(_) => "bar".to_string(),
                 PositionRef::Rail(_) => panic!("Cannot play a piece after bearing it off."),
-                PositionRef::Point(index) => index.normalize(self.player).to_string(),
+                PositionRef::Point(index) =>
+                    index.normalize_on(self.player, self.track).to_string(),
             },
             match self.to {
                 PositionRef::Bar(_) => panic!("Cannot play onto the bar."),
                 PositionRef::Rail(_) => "off".to_string(),
-                PositionRef::Point(index) => index.normalize(self.player).to_string(),
+                PositionRef::Point(index) =>
+                    index.normalize_on(self.player, self.track).to_string(),
             }
         )
     }
@@ -294,11 +372,43 @@ mod tests {
 
     #[test]
     fn empty() {
-        let notation = Notation::new("".to_string(), Player::White);
+        let notation = Notation::new(String::new(), Player::White);
         let turn = turn!(Player::White);
         assert_eq!(notation.turn(), Ok(turn));
     }
 
+    #[test]
+    fn shorthand_notation() {
+        let player = Player::Black;
+        let notation = Notation::new("13/7(2) 8/2*".to_string(), player);
+        let turn = turn!(player, (12, 6), (12, 6), (7, 1));
+        assert_eq!(notation.turn(), Ok(turn));
+
+        let notation = Notation::new("6/off(3) bar/20*/18".to_string(), player);
+        let turn = turn!(player, (5, off), (5, off), (5, off), (bar, 19), (19, 17));
+        assert_eq!(notation.turn(), Ok(turn));
+
+        for input in ["13/7(5)", "13/7(0)", "bar*/20", "8*/2", "8/off*", "13(2)/7"] {
+            let notation = Notation::new(input.to_string(), player);
+            assert_eq!(
+                notation.turn(),
+                Err(Error::InvalidNotation(input.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn turn_display() {
+        let player = Player::Black;
+        let turn = turn!(player, (12, 6), (12, 6), (7, 1), (5, off));
+        assert_eq!(turn.to_string(), "13/7(2) 8/2 6/off");
+
+        let mut board = Board::new();
+        board.point_mut(1).set(1, !player);
+        board.point_mut(6).set(1, !player);
+        assert_eq!(turn.notation(&board), "13/7*(2) 8/2* 6/off");
+    }
+
     #[test]
     fn bad_notation_1() {
         let input = "test123.4abc-30".to_string();
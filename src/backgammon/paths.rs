@@ -0,0 +1,34 @@
+//! Where the files kept between sessions are found.
+
+use std::env;
+use std::path::PathBuf;
+
+/// The directory of this program's files inside the config and data
+/// directories.
+const APP: &str = "backgammon-cli";
+
+/// Finds the config file from `BACKGAMMON_CLI_CONFIG`, or the
+/// `backgammon-cli/config` file in the XDG config directory.
+pub fn config() -> Option<PathBuf> {
+    env::var_os("BACKGAMMON_CLI_CONFIG").map_or_else(
+        || xdg("XDG_CONFIG_HOME", ".config").map(|dir| dir.join(APP).join("config")),
+        |path| Some(path.into()),
+    )
+}
+
+/// Finds a data file from the given environment variable, or the file of that
+/// name in the `backgammon-cli` XDG data directory.
+pub fn data(variable: &str, name: &str) -> Option<PathBuf> {
+    env::var_os(variable).map_or_else(
+        || xdg("XDG_DATA_HOME", ".local/share").map(|dir| dir.join(APP).join(name)),
+        |path| Some(path.into()),
+    )
+}
+
+/// Finds an XDG base directory from its environment variable, falling back to
+/// its default inside the home directory.
+fn xdg(variable: &str, default: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(default)))
+}
@@ -0,0 +1,187 @@
+use crate::backgammon::{
+    Error,
+    board::CHECKERS,
+    compact::{CompactBoard, OFF},
+    dice_roll::SIDES,
+    player::Player,
+    rules::Rules,
+};
+
+/// Gets each of the 21 distinct rolls, smaller die first.
+pub fn rolls() -> impl Iterator<Item = [u8; 2]> {
+    (1..=SIDES).flat_map(|a| (a..=SIDES).map(move |b| [a, b]))
+}
+
+/// Gets the dice available to play for a roll.
+pub fn dice(roll: [u8; 2]) -> Vec<u8> {
+    match roll {
+        [a, b] if a == b => vec![a; 4],
+        roll => roll.to_vec(),
+    }
+}
+
+/// Counts the distinct legal turns for each of the 21 rolls from a position
+/// given by its GNU Backgammon position ID.
+///
+/// # Errors
+///
+/// Returns an error if the position ID is not valid.
+pub fn divide(position_id: &str) -> Result<Vec<([u8; 2], usize)>, Error> {
+    let player = Player::Black;
+    let board = CompactBoard::from_position_id(position_id, player)?;
+
+    Ok(rolls()
+        .map(|roll| {
+            (
+                roll,
+                board.turns(player, &dice(roll), Rules::Standard).len(),
+            )
+        })
+        .collect())
+}
+
+/// Counts the leaves of the game tree `depth` turns deep from a position given
+/// by its GNU Backgammon position ID.
+///
+/// Each turn branches on the 21 rolls and then each distinct legal turn.
+/// Positions where the game is over are leaves.
+///
+/// # Errors
+///
+/// Returns an error if the position ID is not valid.
+pub fn perft(position_id: &str, depth: u32) -> Result<u64, Error> {
+    let player = Player::Black;
+    let board = CompactBoard::from_position_id(position_id, player)?;
+    Ok(count(&board, player, depth))
+}
+
+/// Counts the leaves of the game tree from a board with the player on roll.
+fn count(board: &CompactBoard, player: Player, depth: u32) -> u64 {
+    if depth == 0 || u32::from(board.pieces(!player)[OFF]) == CHECKERS {
+        return 1;
+    }
+
+    rolls()
+        .flat_map(|roll| board.turns(player, &dice(roll), Rules::Standard))
+        .map(|turn| count(&turn.board, !player, depth - 1))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{board::Board, bot::Strategy, dice_roll::DiceRoll, game::Game};
+    use std::collections::HashSet;
+
+    /// Independently counts the distinct legal turns of a game by trying every
+    /// sequence of plays allowed by `Game::check_play`.
+    fn brute_force(game: &Game) -> usize {
+        fn leaves(game: &Game, depth: usize, found: &mut Vec<(usize, Game)>) {
+            let plays = game.get_available_plays();
+            if !game.dice_roll.any_available() || plays.is_empty() {
+                found.push((depth, game.clone()));
+                return;
+            }
+            for play in plays {
+                let mut game = game.clone();
+                game.make_play(&play);
+                leaves(&game, depth + 1, found);
+            }
+        }
+
+        let mut found = vec![];
+        leaves(game, 0, &mut found);
+
+        let most = found.iter().map(|(depth, _)| *depth).max().unwrap_or(0);
+        found.retain(|(depth, _)| *depth == most);
+
+        // When only one of two different dice is used, the smaller must remain.
+        let smaller = game.dice_roll.iter().copied().min().unwrap_or(0);
+        if most == 1 && game.dice_roll.iter().any(|&die| die != smaller) {
+            let larger = |(_, game): &(usize, Game)| game.dice_roll.contains(smaller);
+            if found.iter().any(larger) {
+                found.retain(larger);
+            }
+        }
+
+        found
+            .iter()
+            .map(|(_, game)| CompactBoard::from(&game.board))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn positions() -> Vec<Board> {
+        let player = Player::Black;
+
+        let mut bar = Board::new();
+        bar.point_mut(5).set(4, player);
+        bar.bar_mut(player).set(1, player);
+        bar.point_mut(18).set(4, !player);
+        bar.point_mut(20).set(1, !player);
+
+        let mut bear_off = Board::empty();
+        bear_off.point_mut(0).set(3, player);
+        bear_off.point_mut(2).set(4, player);
+        bear_off.point_mut(5).set(2, player);
+        bear_off.rail_mut(player).set(6, player);
+        bear_off.point_mut(3).set(1, !player);
+        bear_off.point_mut(23).set(14, !player);
+
+        vec![Board::new(), bar, bear_off]
+    }
+
+    #[test]
+    fn opening() {
+        let counts = divide("4HPwATDgc/ABMA").unwrap();
+        assert_eq!(counts.len(), 21);
+        assert!(counts.contains(&([2, 5], 8)));
+        assert_eq!(perft("4HPwATDgc/ABMA", 0), Ok(1));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let player = Player::Black;
+        for board in positions() {
+            let id = CompactBoard::from(&board).position_id(player);
+            for (roll, count) in divide(&id).unwrap() {
+                let game = Game::from(player, DiceRoll::from(roll), board.clone());
+                assert_eq!(count, brute_force(&game), "{id} with {roll:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn tavli_matches_brute_force() {
+        for rules in [Rules::Plakoto, Rules::Fevga] {
+            let mut game = Game::new().with_rules(rules);
+            for _ in 0..20 {
+                let player = game.current_player;
+                let board = CompactBoard::from(&game.board);
+                for roll in rolls() {
+                    let mut game = game.clone();
+                    game.dice_roll = DiceRoll::from(roll);
+                    let count = board.turns(player, &dice(roll), rules).len();
+                    assert_eq!(count, brute_force(&game), "{rules} with {roll:?}");
+                }
+
+                let turn = Strategy::Random.choose_turn(&game);
+                game.take_turn(&turn);
+                game.current_player = !player;
+                game.dice_roll = DiceRoll::new();
+            }
+        }
+    }
+
+    #[test]
+    fn game_over_is_leaf() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.rail_mut(player).set(15, player);
+        board.point_mut(0).set(15, !player);
+
+        // White has no pieces left to play after Black bears off its last.
+        let id = CompactBoard::from(&board).position_id(!player);
+        assert_eq!(perft(&id, 3), Ok(1));
+    }
+}
@@ -1,3 +1,7 @@
+use std::str::FromStr;
+
+use crate::backgammon::Error;
+
 #[derive(Clone, Copy, Eq, Debug, Hash, PartialEq)]
 pub enum Player {
     Black = 0,
@@ -10,6 +14,7 @@ impl Player {
         *self = !*self;
     }
 
+    #[must_use]
     pub fn random() -> Self {
         if rand::random::<bool>() {
             Self::Black
@@ -31,6 +36,18 @@ impl std::ops::Not for Player {
     }
 }
 
+impl FromStr for Player {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "black" | "b" => Ok(Self::Black),
+            "white" | "w" => Ok(Self::White),
+            _ => Err(Error::InvalidPlayer(s.to_owned())),
+        }
+    }
+}
+
 impl std::fmt::Display for Player {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
@@ -58,4 +75,14 @@ mod tests {
         assert_eq!(!Player::White, Player::Black);
         assert_eq!(!Player::None, Player::None);
     }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("Black".parse(), Ok(Player::Black));
+        assert_eq!("w".parse(), Ok(Player::White));
+        assert_eq!(
+            "none".parse::<Player>(),
+            Err(Error::InvalidPlayer("none".to_string()))
+        );
+    }
 }
@@ -0,0 +1,138 @@
+use crate::backgammon::{
+    Error,
+    board::{Board, CHECKERS},
+    compact::{BAR, CompactBoard, OFF},
+    player::Player,
+};
+
+/// Number of bytes in the key of a position ID.
+const KEY_BYTES: usize = 10;
+
+/// Number of characters in a position ID.
+const ID_LENGTH: usize = 14;
+
+/// The alphabet used to encode keys.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl CompactBoard {
+    /// Encodes the board as a [GNU Backgammon position ID](https://www.gnu.org/software/gnubg/manual/html_node/A-technical-description-of-the-Position-ID.html)
+    /// with the given player on roll.
+    ///
+    /// For each player, starting with the opponent, every point from their ace
+    /// to their bar is written as a one bit per piece followed by a zero bit.
+    /// The resulting 80 bits are then encoded in base 64.
+    pub fn position_id(&self, player: Player) -> String {
+        let mut key = [0u8; KEY_BYTES];
+        let mut bit = 0;
+
+        for player in [!player, player] {
+            for &count in &self.pieces(player)[OFF + 1..=BAR] {
+                for _ in 0..count {
+                    if bit < KEY_BYTES * 8 {
+                        key[bit / 8] |= 1 << (bit % 8);
+                    }
+                    bit += 1;
+                }
+                bit += 1;
+            }
+        }
+
+        let mut id = String::with_capacity(ID_LENGTH);
+        for chunk in key.chunks(3) {
+            let bytes = [0, 1, 2].map(|i| u32::from(chunk.get(i).copied().unwrap_or(0)));
+            let group = (bytes[0] << 16) | (bytes[1] << 8) | bytes[2];
+            for i in 0..=chunk.len() {
+                id.push(BASE64[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            }
+        }
+        id
+    }
+
+    /// Decodes a GNU Backgammon position ID with the given player on roll.
+    ///
+    /// Pieces missing from the board are considered borne off.
+    pub fn from_position_id(id: &str, player: Player) -> Result<Self, Error> {
+        let invalid = || Error::InvalidPositionId(id.to_owned());
+
+        let digits: Vec<u32> = id
+            .bytes()
+            .map(|c| BASE64.iter().position(|&b| b == c))
+            .map(|d| d.and_then(|d| u32::try_from(d).ok()))
+            .collect::<Option<_>>()
+            .filter(|digits: &Vec<_>| digits.len() == ID_LENGTH)
+            .ok_or_else(invalid)?;
+
+        let mut key = [0u8; KEY_BYTES];
+        for (i, chunk) in digits.chunks(4).enumerate() {
+            let group = chunk
+                .iter()
+                .enumerate()
+                .fold(0, |group, (j, digit)| group | digit << (18 - 6 * j));
+            for (j, byte) in group.to_be_bytes()[1..].iter().enumerate() {
+                if let Some(k) = key.get_mut(i * 3 + j) {
+                    *k = *byte;
+                }
+            }
+        }
+
+        let mut bits = (0..KEY_BYTES * 8).map(|bit| key[bit / 8] >> (bit % 8) & 1 == 1);
+        let mut board = Self::default();
+
+        for player in [!player, player] {
+            let pieces = board.pieces_mut(player);
+            for count in &mut pieces[OFF + 1..=BAR] {
+                while bits.next().ok_or_else(invalid)? {
+                    *count += 1;
+                }
+            }
+
+            let total: u32 = pieces.iter().copied().map(u32::from).sum();
+            let off = CHECKERS.checked_sub(total).ok_or_else(invalid)?;
+            pieces[OFF] = u8::try_from(off).map_err(|_| invalid())?;
+        }
+
+        Board::from(&board).validate()?;
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opening() {
+        let board = CompactBoard::from(&Board::new());
+        assert_eq!(board.position_id(Player::Black), "4HPwATDgc/ABMA");
+        assert_eq!(
+            CompactBoard::from_position_id("4HPwATDgc/ABMA", Player::White),
+            Ok(board)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut board = Board::new();
+        board.point_mut(5).set(3, Player::Black);
+        board.point_mut(3).set(1, Player::Black);
+        board.bar_mut(Player::Black).set(1, Player::Black);
+        board.point_mut(18).set(2, Player::White);
+        board.rail_mut(Player::White).set(3, Player::White);
+
+        let board = CompactBoard::from(&board);
+        for player in [Player::Black, Player::White] {
+            let id = board.position_id(player);
+            assert_eq!(CompactBoard::from_position_id(&id, player), Ok(board));
+        }
+    }
+
+    #[test]
+    fn invalid_position_id() {
+        for id in ["4HPwATDgc/ABM", "4HPwATDgc/ABM!", "//////////////"] {
+            assert_eq!(
+                CompactBoard::from_position_id(id, Player::Black),
+                Err(Error::InvalidPositionId(id.to_string()))
+            );
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! Named player profiles with ratings and results kept across sessions, rated
+//! with the formula used by the First Internet Backgammon Server.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::{fs, io};
+
+use crate::backgammon::{Error, paths};
+
+/// The rating of a new profile.
+const INITIAL_RATING: f64 = 1500.0;
+/// The experience, in match points, after which rating changes are no longer
+/// boosted.
+const SETTLED: u32 = 400;
+/// The first line of a profiles file.
+const HEADER: &str = "# backgammon-cli profiles";
+
+/// A named player with their rating and results against each opponent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub rating: f64,
+    /// The sum of the lengths of the matches played
+    pub experience: u32,
+    /// The matches won and lost against each opponent by name
+    results: BTreeMap<String, (u32, u32)>,
+}
+
+impl Profile {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            rating: INITIAL_RATING,
+            experience: 0,
+            results: BTreeMap::new(),
+        }
+    }
+
+    /// Gets the matches won and lost against an opponent.
+    #[must_use]
+    pub fn against(&self, opponent: &str) -> (u32, u32) {
+        self.results.get(opponent).copied().unwrap_or_default()
+    }
+
+    /// Gets the matches won and lost against every opponent.
+    #[must_use]
+    pub fn totals(&self) -> (u32, u32) {
+        self.results
+            .values()
+            .fold((0, 0), |(won, lost), &(w, l)| (won + w, lost + l))
+    }
+
+    /// Gets the factor rating changes are multiplied by while the profile is
+    /// new, falling from 5 to 1 over its first 400 points of experience.
+    fn boost(&self) -> f64 {
+        if self.experience >= SETTLED {
+            1.0
+        } else {
+            5.0 - f64::from(self.experience) / 100.0
+        }
+    }
+}
+
+/// Gets the probability that a player wins a match of the given length
+/// against an opponent, from their ratings.
+fn win_probability(rating: f64, opponent: f64, length: u32) -> f64 {
+    let exponent = (opponent - rating) * f64::from(length).sqrt() / 2000.0;
+    1.0 / (10_f64.powf(exponent) + 1.0)
+}
+
+/// The profiles of every player, saved to a file after every match when
+/// loaded from one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profiles {
+    profiles: BTreeMap<String, Profile>,
+    /// The file the profiles are kept in, if any
+    path: Option<PathBuf>,
+}
+
+impl Profiles {
+    /// Creates an empty set of profiles that are not kept after the session.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            profiles: BTreeMap::new(),
+            path: None,
+        }
+    }
+
+    /// Loads the profiles kept in the file from `BACKGAMMON_CLI_PROFILES`, or
+    /// the `backgammon-cli/profiles` file in the XDG data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or is not valid.
+    pub fn load() -> Result<Self, Error> {
+        let Some(path) = paths::data("BACKGAMMON_CLI_PROFILES", "profiles") else {
+            return Ok(Self::new());
+        };
+        let mut profiles = match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::new(),
+            Err(error) => return Err(Error::ProfilesFailed(error.to_string())),
+        };
+        profiles.path = Some(path);
+        Ok(profiles)
+    }
+
+    /// Parses profiles from lines of `player <name> <rating> <experience>`,
+    /// each followed by lines of `result <name> <opponent> <won> <lost>`,
+    /// ignoring blank lines and comments starting with `#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is not valid or a rating is not finite.
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let mut profiles = Self::new();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidProfiles(line.to_owned());
+            let number = |word: &str| word.parse::<u32>().map_err(|_| invalid());
+            let words: Vec<_> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["player", name, rating, experience] => {
+                    check_name(name).map_err(|_| invalid())?;
+                    let mut profile = Profile::new(name);
+                    profile.rating = rating
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|rating| rating.is_finite())
+                        .ok_or_else(invalid)?;
+                    profile.experience = number(experience)?;
+                    profiles.profiles.insert((*name).to_owned(), profile);
+                }
+                ["result", name, opponent, won, lost] => {
+                    check_name(opponent).map_err(|_| invalid())?;
+                    let profile = profiles.profiles.get_mut(*name).ok_or_else(invalid)?;
+                    profile
+                        .results
+                        .insert((*opponent).to_owned(), (number(won)?, number(lost)?));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Gets the profile of a player.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no profile with the name.
+    pub fn get(&self, name: &str) -> Result<&Profile, Error> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| Error::UnknownProfile(name.to_owned()))
+    }
+
+    /// Records a match between two players, creating their profiles if they
+    /// are new, and returns the rating changes of the winner and the loser.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name is not valid or both names are the same.
+    pub fn record_match(
+        &mut self,
+        winner: &str,
+        loser: &str,
+        length: u32,
+    ) -> Result<(f64, f64), Error> {
+        check_name(winner)?;
+        check_name(loser)?;
+        if winner == loser {
+            return Err(Error::InvalidProfileName(loser.to_owned()));
+        }
+
+        let profile = |name: &str| {
+            self.profiles
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| Profile::new(name))
+        };
+        let (mut winner, mut loser) = (profile(winner), profile(loser));
+
+        let change =
+            4.0 * f64::from(length).sqrt() * win_probability(loser.rating, winner.rating, length);
+        let changes = (change * winner.boost(), -change * loser.boost());
+        winner.rating += changes.0;
+        loser.rating += changes.1;
+        winner.experience += length;
+        loser.experience += length;
+        winner.results.entry(loser.name.clone()).or_default().0 += 1;
+        loser.results.entry(winner.name.clone()).or_default().1 += 1;
+
+        self.profiles.insert(winner.name.clone(), winner);
+        self.profiles.insert(loser.name.clone(), loser);
+        Ok(changes)
+    }
+
+    /// Gets the profiles from the highest rating to the lowest.
+    #[must_use]
+    pub fn leaderboard(&self) -> Vec<&Profile> {
+        let mut profiles: Vec<_> = self.profiles.values().collect();
+        profiles.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        profiles
+    }
+
+    /// Saves the profiles to the file they were loaded from, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let failed = |error: io::Error| Error::ProfilesFailed(error.to_string());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(failed)?;
+        }
+        fs::write(path, self.serialize()).map_err(failed)
+    }
+
+    /// Writes the profiles in the format read by [`Profiles::parse`].
+    fn serialize(&self) -> String {
+        let mut lines = vec![HEADER.to_string()];
+        for profile in self.profiles.values() {
+            lines.push(format!(
+                "player {} {} {}",
+                profile.name, profile.rating, profile.experience
+            ));
+            lines.extend(profile.results.iter().map(|(opponent, (won, lost))| {
+                format!("result {} {opponent} {won} {lost}", profile.name)
+            }));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Checks that a name can be kept in the profiles file, which needs it to be a
+/// single word not starting a comment.
+pub fn check_name(name: &str) -> Result<(), Error> {
+    if name.is_empty() || name.starts_with('#') || name.contains(char::is_whitespace) {
+        Err(Error::InvalidProfileName(name.to_owned()))
+    } else {
+        Ok(())
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (won, lost) = self.totals();
+        write!(
+            f,
+            "{}: rating {:.2}, experience {}, won {won} lost {lost}",
+            self.name, self.rating, self.experience
+        )?;
+        for (opponent, (won, lost)) in &self.results {
+            write!(f, "\n  against {opponent}: won {won} lost {lost}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Profiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Leaderboard")?;
+        let width = self.profiles.keys().map(String::len).max().unwrap_or(0);
+        for (rank, profile) in self.leaderboard().into_iter().enumerate() {
+            let (won, lost) = profile.totals();
+            write!(
+                f,
+                "\n  {:>3}. {:<width$} {:>8.2} {:>6} exp  {won}-{lost}",
+                rank + 1,
+                profile.name,
+                profile.rating,
+                profile.experience,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_players() {
+        let mut profiles = Profiles::new();
+        let (gain, loss) = profiles.record_match("alice", "bob", 1).unwrap();
+        assert!((gain - 10.0).abs() < 1e-9);
+        assert!((loss + 10.0).abs() < 1e-9);
+
+        let alice = profiles.get("alice").unwrap();
+        assert!((alice.rating - 1510.0).abs() < 1e-9);
+        assert_eq!(alice.experience, 1);
+        assert_eq!(alice.against("bob"), (1, 0));
+        assert_eq!(profiles.get("bob").unwrap().against("alice"), (0, 1));
+        assert_eq!(
+            profiles.get("carol"),
+            Err(Error::UnknownProfile("carol".to_string()))
+        );
+    }
+
+    #[test]
+    fn match_length_and_ratings() {
+        let mut profiles = Profiles::new();
+        for (name, rating) in [("alice", 1800.0), ("bob", 1500.0), ("carol", 1500.0)] {
+            let mut profile = Profile::new(name);
+            profile.rating = rating;
+            profile.experience = SETTLED;
+            profiles.profiles.insert(name.to_owned(), profile);
+        }
+
+        let (favourite, _) = profiles.clone().record_match("alice", "bob", 1).unwrap();
+        let (underdog, _) = profiles.clone().record_match("bob", "alice", 1).unwrap();
+        assert!(favourite < 2.0 && underdog > 2.0);
+
+        let (short, _) = profiles.clone().record_match("bob", "carol", 1).unwrap();
+        let (long, _) = profiles.record_match("bob", "carol", 9).unwrap();
+        assert!((short - 2.0).abs() < 1e-9);
+        assert!((long - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut profiles = Profiles::new();
+        profiles.record_match("alice", "bob", 5).unwrap();
+        profiles.record_match("carol", "alice", 3).unwrap();
+        profiles.record_match("alice", "bob", 1).unwrap();
+
+        assert_eq!(Profiles::parse(&profiles.serialize()).unwrap(), profiles);
+        assert_eq!(
+            Profiles::parse("result alice bob 1 0"),
+            Err(Error::InvalidProfiles("result alice bob 1 0".to_string()))
+        );
+        for rating in ["NaN", "inf", "-inf"] {
+            let line = format!("player alice {rating} 0");
+            assert_eq!(Profiles::parse(&line), Err(Error::InvalidProfiles(line)));
+        }
+        assert_eq!(
+            profiles.record_match("alice", "bob smith", 1),
+            Err(Error::InvalidProfileName("bob smith".to_string()))
+        );
+    }
+
+    #[test]
+    fn leaderboard() {
+        let mut profiles = Profiles::new();
+        profiles.record_match("carol", "bob", 1).unwrap();
+        profiles.record_match("alice", "bob", 1).unwrap();
+        profiles.record_match("carol", "alice", 1).unwrap();
+
+        let names: Vec<_> = profiles
+            .leaderboard()
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect();
+        assert_eq!(names, ["carol", "alice", "bob"]);
+        assert!(profiles.to_string().contains("1. carol"));
+    }
+}
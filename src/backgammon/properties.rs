@@ -0,0 +1,139 @@
+//! Property-based tests of rule invariants over random legal games.
+
+use proptest::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::backgammon::{
+    board::{BOARD_SIZE, Board, CHECKERS},
+    compact::CompactBoard,
+    dice_roll::{DiceRoll, SIDES},
+    game::Game,
+    notation::Turn,
+    player::Player,
+    rules::Rules,
+};
+
+/// The most turns a game may last before it is considered to never end.
+const MAX_TURNS: usize = 2_000;
+
+/// Rolls the dice of a turn from a seeded generator so games can be replayed.
+fn roll(rng: &mut StdRng) -> DiceRoll<2> {
+    DiceRoll::from([0; 2].map(|_| rng.random_range(1..=SIDES)))
+}
+
+/// Plays a turn a piece at a time, checking the invariants of each play and
+/// that undoing every play restores the game.
+fn check_invariants(game: &mut Game, turn: &Turn) {
+    let player = game.current_player;
+    let before = game.clone();
+    let pips = |game: &Game, player| CompactBoard::from(&game.board).pip_count(player);
+
+    let Turn(plays) = turn;
+    let mut undos = vec![];
+    for play in plays {
+        undos.push(game.make_play(play));
+        for player in [Player::Black, Player::White] {
+            assert_eq!(game.board.checker_count(player), CHECKERS);
+        }
+    }
+
+    // Hits only ever set the opponent back, so the player's own pips drop by
+    // exactly the distance moved.
+    assert_eq!(
+        pips(&before, player) - pips(game, player),
+        turn.distance(&before.board),
+        "{turn}"
+    );
+    assert!(pips(game, !player) >= pips(&before, !player));
+
+    let after = game.clone();
+    for (play, undo) in plays.iter().zip(undos).rev() {
+        game.undo_play(play, undo);
+    }
+    assert_eq!(game.board, before.board);
+    assert_eq!(game.dice_roll, before.dice_roll);
+
+    *game = after;
+}
+
+/// Plays random legal turns from the opening of the rules until someone wins,
+/// returning the number of turns played.
+fn self_play(seed: u64, rules: Rules) -> usize {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let player = if rng.random() {
+        Player::Black
+    } else {
+        Player::White
+    };
+    let mut game = Game::from(player, roll(&mut rng), Board::new()).with_rules(rules);
+
+    for turns in 1..=MAX_TURNS {
+        let available = game.get_available_turns();
+        let (turn, _) = available
+            .choose(&mut rng)
+            .expect("a turn is always available");
+
+        assert_eq!(game.check_turn(turn), Ok(()), "{turn}");
+        check_invariants(&mut game, turn);
+
+        let player = game.current_player;
+        if game.board.all_in_rail(player) || rules.pins_mother(&game.board, player) {
+            return turns;
+        }
+        game.current_player.switch();
+        game.dice_roll = roll(&mut rng);
+    }
+
+    panic!("game did not end within {MAX_TURNS} turns");
+}
+
+/// Creates a random bear-off position, with each player's pieces spread over
+/// their home board and rail.
+fn bear_off(seed: u64) -> Game {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut board = Board::empty();
+
+    for player in [Player::Black, Player::White] {
+        for _ in 0..CHECKERS {
+            match rng.random_range(0..=6) {
+                0 => board.rail_mut(player).count += 1,
+                point => {
+                    let index = match player {
+                        Player::White => BOARD_SIZE - point,
+                        _ => point - 1,
+                    };
+                    let position = board.point_mut(index);
+                    position.set(position.count + 1, player);
+                }
+            }
+        }
+    }
+
+    Game::from(Player::Black, roll(&mut rng), board)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn random_games_end(seed in any::<u64>()) {
+        for rules in [Rules::Standard, Rules::Plakoto, Rules::Fevga] {
+            let turns = self_play(seed, rules);
+            prop_assert!(turns > 0);
+        }
+    }
+
+    #[test]
+    fn bear_off_turns_are_legal(seed in any::<u64>()) {
+        let game = bear_off(seed);
+        prop_assume!(!game.board.all_in_rail(Player::Black));
+
+        for (turn, board) in game.get_available_turns() {
+            prop_assert_eq!(game.check_turn(&turn), Ok(()), "{}", turn);
+
+            let mut played = game.clone();
+            check_invariants(&mut played, &turn);
+            prop_assert_eq!(CompactBoard::from(&played.board), board);
+        }
+    }
+}
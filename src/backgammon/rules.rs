@@ -0,0 +1,378 @@
+//! Rules of games played on a backgammon board that differ from backgammon
+//! in how pieces enter, hit and move, and how turns are rolled.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::backgammon::{
+    Error,
+    board::{BOARD_SIZE, Board, CHECKERS},
+    location::Track,
+    player::Player,
+    win::Win,
+};
+
+/// The rules a game is played by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rules {
+    #[default]
+    Standard,
+    /// Every piece starts off the board and enters like a piece on the bar,
+    /// though pieces may be moved before every piece has entered. A player
+    /// rolling 1-2 plays it, then chooses any doubles to play and rolls
+    /// again.
+    AceyDeucey,
+    /// The first game of tavli, played like backgammon except that a win is
+    /// worth at most a gammon.
+    Portes,
+    /// The second game of tavli, where every piece starts on the 24-point and
+    /// a piece landing on a blot pins it in place instead of hitting it. A
+    /// player pinning the opponent's last piece on their 24-point wins a
+    /// gammon.
+    Plakoto,
+    /// The third game of tavli, where both players move the same way around
+    /// the board from opposite corners. Pieces cannot hit, so a single piece
+    /// holds a point.
+    ///
+    /// The rules about moving the first piece past the opponent's starting
+    /// point before others, and about priming every opponent piece, are not
+    /// enforced.
+    Fevga,
+}
+
+/// The rules of each game of a tavli match, played in turn.
+pub const TAVLI: [Rules; 3] = [Rules::Portes, Rules::Plakoto, Rules::Fevga];
+
+impl Rules {
+    /// Creates the starting position of the rules.
+    #[must_use]
+    pub fn board(self) -> Board {
+        match self {
+            Self::Standard | Self::Portes => Board::new(),
+            Self::Plakoto | Self::Fevga => {
+                let mut board = Board::empty_on(self.track());
+                for player in [Player::Black, Player::White] {
+                    let point = board.position_ref(BOARD_SIZE, player);
+                    for _ in 0..CHECKERS {
+                        let start = board.get_mut(&point);
+                        start.player = player;
+                        start.count += 1;
+                    }
+                }
+                board
+            }
+            Self::AceyDeucey => {
+                let mut board = Board::empty();
+                for player in [Player::Black, Player::White] {
+                    for _ in 0..CHECKERS {
+                        board.bar_mut(player).count += 1;
+                    }
+                }
+                board
+            }
+        }
+    }
+
+    /// Checks whether a player with pieces on the bar must enter them before
+    /// playing any other piece.
+    #[must_use]
+    pub const fn enters_first(self) -> bool {
+        !matches!(self, Self::AceyDeucey)
+    }
+
+    /// Gets the track the players move along.
+    #[must_use]
+    pub const fn track(self) -> Track {
+        match self {
+            Self::Fevga => Track::Same,
+            _ => Track::Opposite,
+        }
+    }
+
+    /// Checks whether a piece landing on a single opposing piece pins it
+    /// instead of hitting it.
+    #[must_use]
+    pub const fn pins(self) -> bool {
+        matches!(self, Self::Plakoto)
+    }
+
+    /// Checks whether a piece is kept from landing on a point holding the
+    /// given number of opposing pieces, which may be pinning a piece of the
+    /// player.
+    #[must_use]
+    pub const fn is_blocked(self, count: u8, pinning: bool) -> bool {
+        match self {
+            Self::Fevga => count > 0,
+            Self::Plakoto => count > 1 || (count > 0 && pinning),
+            _ => count > 1,
+        }
+    }
+
+    /// Limits a win to what it is worth under the rules, as tavli games have
+    /// no backgammons.
+    #[must_use]
+    pub fn limit(self, win: Win) -> Win {
+        match self {
+            Self::Portes | Self::Plakoto | Self::Fevga => win.min(Win::Gammon),
+            _ => win,
+        }
+    }
+
+    /// Checks whether the player has pinned the last piece of their opponent
+    /// on its starting point, winning a gammon in plakoto.
+    #[must_use]
+    pub fn pins_mother(self, board: &Board, player: Player) -> bool {
+        let start = board.get(&board.position_ref(BOARD_SIZE, !player));
+        self.pins() && start.pinned && start.player == player
+    }
+
+    /// Checks whether playing the whole of a roll earns the player doubles of
+    /// their choice followed by another roll.
+    #[must_use]
+    pub const fn is_bonus(self, dice: [u8; 2]) -> bool {
+        matches!(self, Self::AceyDeucey) && matches!(dice, [1, 2] | [2, 1])
+    }
+}
+
+impl FromStr for Rules {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" | "backgammon" => Ok(Self::Standard),
+            "acey-deucey" | "aceydeucey" => Ok(Self::AceyDeucey),
+            "portes" => Ok(Self::Portes),
+            "plakoto" => Ok(Self::Plakoto),
+            "fevga" => Ok(Self::Fevga),
+            _ => Err(Error::InvalidRules(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Standard => "standard",
+            Self::AceyDeucey => "acey-deucey",
+            Self::Portes => "portes",
+            Self::Plakoto => "plakoto",
+            Self::Fevga => "fevga",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{
+        bot::Strategy,
+        compact::CompactBoard,
+        dice_roll::DiceRoll,
+        game::Game,
+        notation::{Play, PositionRef, Turn, turn},
+    };
+
+    #[test]
+    fn acey_deucey_start() {
+        let board = Rules::AceyDeucey.board();
+        assert_eq!(board.validate(), Ok(()));
+        for player in [Player::Black, Player::White] {
+            assert_eq!(u32::from(board.bar(player).count), CHECKERS);
+        }
+
+        let game = Game::from(Player::Black, DiceRoll::from([6, 5]), Board::empty())
+            .with_rules(Rules::AceyDeucey);
+        let turns = game.get_available_turns();
+        assert_eq!(turns.len(), 2);
+        assert!(game.read_turn("bar/19 bar/20").is_ok());
+        assert!(game.read_turn("bar/14").is_ok());
+    }
+
+    #[test]
+    fn moving_before_entering() {
+        let player = Player::Black;
+        let mut board = Rules::AceyDeucey.board();
+        board.bar_mut(player).count -= 1;
+        board.point_mut(10).set(1, player);
+
+        let mut game = Game::from(player, DiceRoll::from([4, 3]), board);
+        let play = Play::new(
+            player,
+            PositionRef::Point(10.try_into().unwrap()),
+            PositionRef::Point(6.try_into().unwrap()),
+        );
+        assert_eq!(game.check_play(&play), Err(Error::PlayMadeWithBarFilled));
+
+        game.rules = Rules::AceyDeucey;
+        assert_eq!(game.check_play(&play), Ok(()));
+        assert!(game.get_available_plays().contains(&play));
+        for (turn, _) in game.get_available_turns() {
+            assert_eq!(game.check_turn(&turn), Ok(()));
+        }
+        assert_eq!(game.check_turn(&turn!(player, (10, 6), (6, 3))), Ok(()));
+    }
+
+    #[test]
+    fn bots_finish() {
+        let mut game = Game::new().with_rules(Rules::AceyDeucey);
+        for _ in 0..1000 {
+            let player = game.current_player;
+            let turn = Strategy::Heuristic.choose_turn(&game);
+            assert_eq!(game.check_turn(&turn), Ok(()));
+            game.take_turn(&turn);
+            if game.board.all_in_rail(player) {
+                return;
+            }
+            game.current_player = !player;
+            game.dice_roll = DiceRoll::new();
+        }
+        panic!("game did not finish");
+    }
+
+    #[test]
+    fn tavli_starts() {
+        for rules in TAVLI {
+            let board = rules.board();
+            assert_eq!(board.validate(), Ok(()));
+            assert_eq!(board.track(), rules.track());
+        }
+
+        let board = Rules::Plakoto.board();
+        assert_eq!(board.point(23).count, 15);
+        assert_eq!(board.point(0).player, Player::White);
+
+        // In fevga, White starts in the corner to the left of Black's home.
+        let board = Rules::Fevga.board();
+        assert_eq!(board.point(11).count, 15);
+        assert_eq!(board.point(11).player, Player::White);
+        for player in [Player::Black, Player::White] {
+            assert_eq!(board.pip_count(player), 15 * 24);
+        }
+    }
+
+    #[test]
+    fn plakoto_pins() {
+        let player = Player::Black;
+        let mut board = Rules::Plakoto.board();
+        board.point_mut(23).count -= 2;
+        board.point_mut(9).set(2, player);
+        board.point_mut(0).count -= 1;
+        board.point_mut(6).set(1, !player);
+
+        let mut game = Game::from(player, DiceRoll::from([3, 1]), board);
+        game.rules = Rules::Plakoto;
+        game.take_turn(&game.read_turn("10/7 24/23").unwrap());
+        let pinned = game.board.point(6);
+        assert_eq!(
+            (pinned.count, pinned.player, pinned.pinned),
+            (1, player, true)
+        );
+        assert_eq!(game.board.bar(!player).count, 0);
+        assert_eq!(game.board.checker_count(!player), CHECKERS);
+        let compact = CompactBoard::from(&game.board);
+        assert_eq!(game.board.pip_count(!player), compact.pip_count(!player));
+
+        // The pinned piece cannot move and its point cannot be landed on.
+        game.current_player = !player;
+        game.dice_roll = DiceRoll::from([6, 6]);
+        let pin = PositionRef::Point(6.try_into().unwrap());
+        assert!(
+            game.get_available_plays()
+                .iter()
+                .all(|play| play.from != pin && play.to != pin)
+        );
+        game.dice_roll = DiceRoll::from([1, 6]);
+        assert_eq!(
+            game.read_turn("1/7").map_err(|_| ()),
+            Err(()),
+            "White cannot land on a point pinning its piece"
+        );
+
+        // Leaving the point frees the pinned piece.
+        game.current_player = player;
+        game.dice_roll = DiceRoll::from([2, 1]);
+        game.take_turn(&game.read_turn("7/5 10/9").unwrap());
+        let freed = game.board.point(6);
+        assert_eq!(
+            (freed.count, freed.player, freed.pinned),
+            (1, !player, false)
+        );
+    }
+
+    #[test]
+    fn plakoto_mother() {
+        let player = Player::Black;
+        let mut board = Rules::Plakoto.board();
+        board.point_mut(23).count -= 1;
+        board.point_mut(1).set(1, player);
+        board.point_mut(0).count = 1;
+        board.rail_mut(!player).set(14, !player);
+
+        let mut game = Game::from(player, DiceRoll::from([1, 6]), board);
+        game.rules = Rules::Plakoto;
+        assert!(!Rules::Plakoto.pins_mother(&game.board, player));
+        game.take_turn(&game.read_turn("2/1 24/18").unwrap());
+        assert!(Rules::Plakoto.pins_mother(&game.board, player));
+        assert!(!Rules::Fevga.pins_mother(&game.board, player));
+        assert_eq!(Rules::Portes.limit(Win::Backgammon), Win::Gammon);
+    }
+
+    #[test]
+    fn fevga_blocks() {
+        let player = Player::White;
+        let mut game = Game::from(player, DiceRoll::from([6, 5]), Rules::Fevga.board());
+        game.rules = Rules::Fevga;
+
+        // White moves from its corner towards Black's home board.
+        let turn = game.read_turn("24/18 24/19").unwrap();
+        assert_eq!(turn.to_string(), "24/18 24/19");
+        game.take_turn(&turn);
+        assert_eq!(game.board.point(5).player, player);
+        assert_eq!(game.board.point(6).player, player);
+
+        // A single piece holds a point, so Black cannot land on White's 6- or
+        // 7-point with a 6 or a 5 from its 24-point.
+        let mut board = Rules::Fevga.board();
+        board.point_mut(11).count -= 2;
+        board.point_mut(17).set(1, player);
+        board.point_mut(18).set(1, player);
+        let mut game = Game::from(!player, DiceRoll::from([6, 5]), board);
+        game.rules = Rules::Fevga;
+        assert!(game.get_available_plays().is_empty());
+
+        game.dice_roll = DiceRoll::from([6, 4]);
+        assert!(game.read_turn("24/20/14").is_ok());
+        assert!(game.read_turn("24/18 24/20").is_err());
+    }
+
+    #[test]
+    fn tavli_bots_finish() {
+        for rules in TAVLI {
+            let mut game = Game::new().with_rules(rules);
+            let finished = (0..2000).any(|_| {
+                let player = game.current_player;
+                let turn = Strategy::Heuristic.choose_turn(&game);
+                assert_eq!(game.check_turn(&turn), Ok(()), "{rules}");
+                game.take_turn(&turn);
+                game.current_player = !player;
+                game.dice_roll = DiceRoll::new();
+                game.board.all_in_rail(player) || rules.pins_mother(&game.board, player)
+            });
+            assert!(finished, "{rules} game did not finish");
+        }
+    }
+
+    #[test]
+    fn bonus() {
+        assert!(Rules::AceyDeucey.is_bonus([2, 1]));
+        assert_eq!("Plakoto".parse(), Ok(Rules::Plakoto));
+        assert_eq!(Rules::Fevga.to_string(), "fevga");
+        assert!(!Rules::AceyDeucey.is_bonus([2, 2]));
+        assert!(!Rules::Standard.is_bonus([1, 2]));
+        assert_eq!("Acey-Deucey".parse(), Ok(Rules::AceyDeucey));
+        assert_eq!(
+            "tavla".parse::<Rules>(),
+            Err(Error::InvalidRules("tavla".to_string()))
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use colored::Colorize;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::backgammon::{
+    Error,
+    board::Board,
+    diagram::Diagram,
+    dice_roll::{DiceRoll, SIDES},
+    game::Game,
+    location::Normalized,
+    player::Player,
+};
+
+const HELP: &str = "\
+put <point> <count> <player>  set the pieces on a point from the player's perspective
+bar <player> <count>          set the pieces in the player's bar
+off <player> <count>          set the pieces borne off by the player
+clear                         remove every piece from the board
+dice <die> <die>              set the dice of the player to play
+turn <player>                 set the player to play
+diagram <file>                save an SVG (or PNG) picture of the position
+play                          start playing from the position
+help                          show this message";
+
+/// A single command of the position editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Put(usize, u8, Player),
+    Bar(Player, u8),
+    Off(Player, u8),
+    Clear,
+    Dice(u8, u8),
+    Turn(Player),
+    Diagram(String),
+    Play,
+    Help,
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidSetupCommand(s.trim().to_owned());
+        let words: Vec<_> = s.split_whitespace().collect();
+
+        let number = |word: &str| word.parse::<u8>().map_err(|_| invalid());
+        let die = |word: &str| {
+            number(word).and_then(|die| {
+                if (1..=SIDES).contains(&die) {
+                    Ok(die)
+                } else {
+                    Err(invalid())
+                }
+            })
+        };
+
+        match words.as_slice() {
+            ["put", point, count, player] => Ok(Self::Put(
+                point.parse().map_err(|_| invalid())?,
+                number(count)?,
+                player.parse()?,
+            )),
+            ["bar", player, count] => Ok(Self::Bar(player.parse()?, number(count)?)),
+            ["off", player, count] => Ok(Self::Off(player.parse()?, number(count)?)),
+            ["clear"] => Ok(Self::Clear),
+            ["dice", a, b] => Ok(Self::Dice(die(a)?, die(b)?)),
+            ["turn", player] => Ok(Self::Turn(player.parse()?)),
+            ["diagram", path] => Ok(Self::Diagram((*path).to_owned())),
+            ["play" | "start"] => Ok(Self::Play),
+            ["help"] => Ok(Self::Help),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A position editor used to enter a position before playing from it.
+pub struct Setup {
+    /// The position being edited
+    board: Board,
+    /// The dice of the player to play, rolled when play starts if unset
+    dice: Option<[u8; 2]>,
+    /// The player to play
+    player: Player,
+}
+
+impl Setup {
+    /// Create a `Setup` starting from the standard position.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            board: Board::new(),
+            dice: None,
+            player: Player::Black,
+        }
+    }
+
+    /// Reads commands until a valid position is played, returning the game
+    /// starting from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from standard input fails.
+    pub fn start(mut self) -> io::Result<Game> {
+        println!("{HELP}");
+        loop {
+            match self.player {
+                Player::White => println!("\n{:#}\n", self.board),
+                _ => println!("\n{}\n", self.board),
+            }
+
+            let prompt = match self.dice {
+                Some([a, b]) => format!("setup ({} to play {a}-{b}): ", self.player),
+                None => format!("setup ({} to play): ", self.player),
+            };
+            print!("{}", prompt.green().italic());
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            let result = input.parse().and_then(|command| self.apply(command));
+
+            match result {
+                Ok(Some(game)) => return Ok(game),
+                Ok(None) => {}
+                Err(error) => println!("{}", error.to_string().red().bold()),
+            }
+        }
+    }
+
+    /// Applies a command to the position, returning the game to play once the
+    /// position is played.
+    fn apply(&mut self, command: Command) -> Result<Option<Game>, Error> {
+        match command {
+            Command::Put(point, count, player) => {
+                let index = Normalized::new(point, player)?.to_index()?;
+                let owner = if count == 0 { Player::None } else { player };
+                self.board.point_mut(*index).set(count, owner);
+            }
+            Command::Bar(player, count) => self.board.bar_mut(player).set(count, player),
+            Command::Off(player, count) => self.board.rail_mut(player).set(count, player),
+            Command::Clear => self.board = Board::empty(),
+            Command::Dice(a, b) => self.dice = Some([a, b]),
+            Command::Turn(player) => self.player = player,
+            Command::Diagram(path) => {
+                let diagram = Diagram::new(&self.board, self.player);
+                let diagram = match self.dice {
+                    Some(dice) => diagram.with_dice(dice),
+                    None => diagram,
+                };
+                diagram.save(Path::new(&path))?;
+                println!("saved diagram to {path}");
+            }
+            Command::Play => return self.game().map(Some),
+            Command::Help => println!("{HELP}"),
+        }
+        Ok(None)
+    }
+
+    /// Creates a game from the position, ensuring it is legal.
+    fn game(&self) -> Result<Game, Error> {
+        self.board.validate()?;
+
+        for player in [Player::Black, Player::White] {
+            if self.board.all_in_rail(player) {
+                return Err(Error::GameAlreadyWon(player));
+            }
+        }
+
+        let dice = self.dice.map_or_else(DiceRoll::new, DiceRoll::from);
+        Ok(Game::from(self.player, dice, self.board.clone()))
+    }
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{Violation, board::CHECKERS};
+
+    fn run(setup: &mut Setup, commands: &[&str]) -> Result<Option<Game>, Error> {
+        let mut result = Ok(None);
+        for command in commands {
+            result = command.parse().and_then(|command| setup.apply(command));
+            if result.is_err() {
+                break;
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            "put 6 3 black".parse(),
+            Ok(Command::Put(6, 3, Player::Black))
+        );
+        assert_eq!("bar white 1".parse(), Ok(Command::Bar(Player::White, 1)));
+        assert_eq!("off b 5".parse(), Ok(Command::Off(Player::Black, 5)));
+        assert_eq!("dice 6 2".parse(), Ok(Command::Dice(6, 2)));
+        assert_eq!("turn white".parse(), Ok(Command::Turn(Player::White)));
+        assert_eq!(
+            "dice 7 2".parse::<Command>(),
+            Err(Error::InvalidSetupCommand("dice 7 2".to_string()))
+        );
+        assert_eq!(
+            "put 6 3 red".parse::<Command>(),
+            Err(Error::InvalidPlayer("red".to_string()))
+        );
+    }
+
+    #[test]
+    fn enter_position() {
+        let mut setup = Setup::new();
+        let game = run(
+            &mut setup,
+            &[
+                "clear",
+                "put 6 5 black",
+                "put 5 5 black",
+                "off black 5",
+                "put 1 2 white",
+                "bar white 1",
+                "off white 12",
+                "dice 6 2",
+                "turn white",
+                "play",
+            ],
+        )
+        .unwrap()
+        .unwrap();
+
+        let mut board = Board::empty();
+        board.point_mut(5).set(5, Player::Black);
+        board.point_mut(4).set(5, Player::Black);
+        board.rail_mut(Player::Black).set(5, Player::Black);
+        board.point_mut(23).set(2, Player::White);
+        board.bar_mut(Player::White).set(1, Player::White);
+        board.rail_mut(Player::White).set(12, Player::White);
+
+        assert_eq!(game.board, board);
+        assert_eq!(game.current_player, Player::White);
+        assert_eq!(game.dice_roll, DiceRoll::from([6, 2]));
+    }
+
+    #[test]
+    fn put_zero_clears_point() {
+        let mut setup = Setup::new();
+        assert_eq!(
+            run(&mut setup, &["put 6 0 black"]).map(|g| g.is_none()),
+            Ok(true)
+        );
+        assert_eq!(setup.board.point(5).player, Player::None);
+    }
+
+    #[test]
+    fn invalid_checker_count() {
+        let mut setup = Setup::new();
+        let result = run(&mut setup, &["put 6 4 black", "play"]);
+        assert_eq!(
+            result.err(),
+            Some(Error::InvalidBoard(vec![Violation::CheckerCount(
+                Player::Black,
+                14,
+                CHECKERS
+            )]))
+        );
+    }
+
+    #[test]
+    fn game_already_won() {
+        let mut setup = Setup::new();
+        let result = run(
+            &mut setup,
+            &["clear", "off black 15", "put 1 15 white", "play"],
+        );
+        assert_eq!(result.err(), Some(Error::GameAlreadyWon(Player::Black)));
+    }
+
+    #[test]
+    fn invalid_point() {
+        let mut setup = Setup::new();
+        let result = run(&mut setup, &["put 25 1 white"]);
+        assert_eq!(result.err(), Some(Error::InvalidIndexLocation(25)));
+    }
+}
@@ -0,0 +1,399 @@
+//! Statistics of the dice each player rolls, kept across sessions to settle
+//! arguments about luck and to check the dice are fair.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::{fs, io};
+
+use crate::backgammon::{Error, dice_roll::SIDES, paths, player::Player};
+
+/// The number of distinct rolls, one more than the degrees of freedom of the
+/// chi-square check.
+const ROLLS: usize = 21;
+
+/// The first line of a statistics file.
+const HEADER: &str = "# backgammon-cli dice statistics";
+
+/// The dice rolled by a single player.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// The times each roll came up, by its smaller then larger die
+    rolls: [[u32; SIDES as usize]; SIDES as usize],
+    /// The turns started with pieces on the bar
+    bar_turns: u32,
+    /// The turns started with pieces on the bar where any piece entered
+    entered: u32,
+}
+
+impl Tally {
+    const fn new() -> Self {
+        Self {
+            rolls: [[0; SIDES as usize]; SIDES as usize],
+            bar_turns: 0,
+            entered: 0,
+        }
+    }
+
+    /// Gets the times a roll came up, in either order.
+    #[must_use]
+    pub fn count(&self, [a, b]: [u8; 2]) -> u32 {
+        let (low, high) = (a.min(b), a.max(b));
+        self.rolls[usize::from(low) - 1][usize::from(high) - 1]
+    }
+
+    /// Gets every distinct roll with the times it came up.
+    fn rolls(&self) -> impl Iterator<Item = ([u8; 2], u32)> + '_ {
+        (1..=SIDES)
+            .flat_map(|a| (a..=SIDES).map(move |b| [a, b]))
+            .map(|roll| (roll, self.count(roll)))
+    }
+
+    /// Gets the number of rolls.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.rolls().map(|(_, count)| count).sum()
+    }
+
+    /// Gets the number of doubles rolled.
+    #[must_use]
+    pub fn doubles(&self) -> u32 {
+        (1..=SIDES).map(|die| self.count([die, die])).sum()
+    }
+
+    /// Gets the times each face came up on either die.
+    #[must_use]
+    pub fn faces(&self) -> [u32; SIDES as usize] {
+        let mut faces = [0; SIDES as usize];
+        for ([a, b], count) in self.rolls() {
+            faces[usize::from(a) - 1] += count;
+            faces[usize::from(b) - 1] += count;
+        }
+        faces
+    }
+
+    /// Gets the chi-square statistic of the rolls against fair dice, with the
+    /// probability of one at least as large from fair dice.
+    #[must_use]
+    pub fn chi_square(&self) -> (f64, f64) {
+        let total = f64::from(self.total());
+        if total == 0.0 {
+            return (0.0, 1.0);
+        }
+
+        let statistic = self
+            .rolls()
+            .map(|([a, b], count)| {
+                let expected = total * if a == b { 1.0 } else { 2.0 } / 36.0;
+                (f64::from(count) - expected).powi(2) / expected
+            })
+            .sum();
+        (statistic, p_value(statistic, ROLLS - 1))
+    }
+}
+
+/// The dice rolled by each player, saved to a file after every roll when
+/// loaded from one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiceStats {
+    /// The tallies of `Player::Black` and `Player::White` respectively
+    tallies: [Tally; 2],
+    /// The file the statistics are kept in, if any
+    path: Option<PathBuf>,
+}
+
+impl DiceStats {
+    /// Creates empty statistics that are not kept after the session.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tallies: [Tally::new(), Tally::new()],
+            path: None,
+        }
+    }
+
+    /// Loads the statistics kept in the file from `BACKGAMMON_CLI_STATS`, or
+    /// the `backgammon-cli/dice` file in the XDG data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or is not valid.
+    pub fn load() -> Result<Self, Error> {
+        let Some(path) = paths::data("BACKGAMMON_CLI_STATS", "dice") else {
+            return Ok(Self::new());
+        };
+
+        let mut stats = match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::new(),
+            Err(error) => return Err(Error::StatsFailed(error.to_string())),
+        };
+        stats.path = Some(path);
+        Ok(stats)
+    }
+
+    /// Parses statistics from lines of `<player> <die>-<die> <count>` for the
+    /// rolls and `<player> bar <turns> <entered>` for entering from the bar,
+    /// ignoring blank lines and comments starting with `#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is not valid.
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let mut stats = Self::new();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || Error::InvalidStats(line.to_owned());
+            let number = |word: &str| word.parse::<u32>().map_err(|_| invalid());
+            let die = |word: &str| match word.parse::<u8>() {
+                Ok(die) if (1..=SIDES).contains(&die) => Ok(usize::from(die) - 1),
+                _ => Err(invalid()),
+            };
+
+            let words: Vec<_> = line.split_whitespace().collect();
+            let [player, kind, counts @ ..] = words.as_slice() else {
+                return Err(invalid());
+            };
+            let tally = stats.tally_mut(player.parse()?);
+
+            match (kind.split_once('-'), counts) {
+                (None, [turns, entered]) if *kind == "bar" => {
+                    tally.bar_turns = number(turns)?;
+                    tally.entered = number(entered)?;
+                }
+                (Some((a, b)), [count]) => {
+                    let (a, b) = (die(a)?, die(b)?);
+                    tally.rolls[a.min(b)][a.max(b)] = number(count)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Gets the dice rolled by a player.
+    #[must_use]
+    pub const fn tally(&self, player: Player) -> &Tally {
+        &self.tallies[player as usize]
+    }
+
+    const fn tally_mut(&mut self, player: Player) -> &mut Tally {
+        &mut self.tallies[player as usize]
+    }
+
+    /// Counts a roll of the player.
+    pub fn record_roll(&mut self, player: Player, [a, b]: [u8; 2]) {
+        let (low, high) = (usize::from(a.min(b)) - 1, usize::from(a.max(b)) - 1);
+        self.tally_mut(player).rolls[low][high] += 1;
+    }
+
+    /// Counts a turn the player started with pieces on the bar, and whether
+    /// any of them entered.
+    pub fn record_bar(&mut self, player: Player, entered: bool) {
+        let tally = self.tally_mut(player);
+        tally.bar_turns += 1;
+        tally.entered += u32::from(entered);
+    }
+
+    /// Saves the statistics to the file they were loaded from, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let failed = |error: io::Error| Error::StatsFailed(error.to_string());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(failed)?;
+        }
+        fs::write(path, self.serialize()).map_err(failed)
+    }
+
+    /// Writes the statistics in the format read by [`DiceStats::parse`].
+    fn serialize(&self) -> String {
+        let mut lines = vec![HEADER.to_string()];
+        for player in [Player::Black, Player::White] {
+            let tally = self.tally(player);
+            let name = player.to_string().to_lowercase();
+            lines.extend(
+                tally
+                    .rolls()
+                    .filter(|&(_, count)| count > 0)
+                    .map(|([a, b], count)| format!("{name} {a}-{b} {count}")),
+            );
+            lines.push(format!("{name} bar {} {}", tally.bar_turns, tally.entered));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Formats a share of a total as a percentage.
+fn percent(count: u32, total: u32) -> String {
+    if total == 0 {
+        "-".to_string()
+    } else {
+        format!("{:.1}%", f64::from(count) * 100.0 / f64::from(total))
+    }
+}
+
+impl Display for DiceStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, player) in [Player::Black, Player::White].into_iter().enumerate() {
+            let tally = self.tally(player);
+            let total = tally.total();
+            let (statistic, p) = tally.chi_square();
+
+            if index > 0 {
+                write!(f, "\n\n")?;
+            }
+            writeln!(f, "{player}: {total} rolls")?;
+            writeln!(
+                f,
+                "  doubles {} ({}, fair 16.7%)",
+                tally.doubles(),
+                percent(tally.doubles(), total)
+            )?;
+            writeln!(
+                f,
+                "  entered from the bar {} of {} turns ({})",
+                tally.entered,
+                tally.bar_turns,
+                percent(tally.entered, tally.bar_turns)
+            )?;
+
+            let faces = tally.faces();
+            let faces = (1..=SIDES)
+                .zip(faces)
+                .map(|(face, count)| format!("{face}: {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "  faces {faces}")?;
+
+            write!(f, "  rolls")?;
+            for a in 1..=SIDES {
+                write!(f, "\n   ")?;
+                for b in 1..=SIDES {
+                    match b {
+                        b if b < a => write!(f, "{:>9}", "")?,
+                        b => write!(f, "{:>9}", format!("{a}-{b}: {}", tally.count([a, b])))?,
+                    }
+                }
+            }
+            write!(
+                f,
+                "\n  chi-square {statistic:.2} with {} degrees of freedom, p = {p:.3}",
+                ROLLS - 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Gets the probability of a chi-square statistic at least as large as the
+/// given one from fair dice.
+fn p_value(statistic: f64, freedom: usize) -> f64 {
+    let freedom = f64::from(u32::try_from(freedom).unwrap_or(u32::MAX));
+    (1.0 - lower_gamma(freedom / 2.0, statistic / 2.0)).clamp(0.0, 1.0)
+}
+
+/// Computes the regularized lower incomplete gamma function by its series.
+fn lower_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut n = a;
+    loop {
+        n += 1.0;
+        term *= x / n;
+        sum += term;
+        if term <= sum * 1e-12 {
+            break;
+        }
+    }
+    a.mul_add(x.ln(), sum.ln() - x - ln_gamma(a)).exp()
+}
+
+/// Computes the natural logarithm of the gamma function with the Lanczos
+/// approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS
+        .iter()
+        .skip(1)
+        .zip(1..)
+        .fold(COEFFICIENTS[0], |sum, (coefficient, i)| {
+            sum + coefficient / (x + f64::from(i))
+        });
+    let ln_root_two_pi = 0.5 * std::f64::consts::TAU.ln();
+    (x + 0.5).mul_add(t.ln(), ln_root_two_pi - t + series.ln())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut stats = DiceStats::new();
+        stats.record_roll(Player::Black, [6, 1]);
+        stats.record_roll(Player::Black, [1, 6]);
+        stats.record_roll(Player::White, [3, 3]);
+        stats.record_bar(Player::White, false);
+        stats.record_bar(Player::White, true);
+
+        assert_eq!(stats.tally(Player::Black).count([1, 6]), 2);
+        assert_eq!(stats.tally(Player::White).doubles(), 1);
+        assert_eq!(stats.tally(Player::White).faces()[2], 2);
+        assert_eq!(DiceStats::parse(&stats.serialize()), Ok(stats));
+        assert_eq!(
+            DiceStats::parse("black 7-1 3"),
+            Err(Error::InvalidStats("black 7-1 3".to_string()))
+        );
+    }
+
+    #[test]
+    fn chi_square() {
+        let mut stats = DiceStats::new();
+        for a in 1..=SIDES {
+            for b in 1..=SIDES {
+                stats.record_roll(Player::Black, [a, b]);
+                stats.record_roll(Player::White, [a, a]);
+            }
+        }
+
+        let (statistic, p) = stats.tally(Player::Black).chi_square();
+        assert!(statistic.abs() < 1e-9);
+        assert!((p - 1.0).abs() < 1e-9);
+
+        let (_, p) = stats.tally(Player::White).chi_square();
+        assert!(p < 1e-6);
+    }
+
+    #[test]
+    fn p_values() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-9);
+        assert!((p_value(31.41, 20) - 0.05).abs() < 1e-3);
+        assert!((p_value(11.07, 5) - 0.05).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,362 @@
+use colored::{ColoredString, Colorize};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::{env, fs, io};
+
+use crate::backgammon::{Error, paths, player::Player};
+
+/// The theme used when displaying boards.
+static CURRENT: RwLock<Theme> = RwLock::new(Theme::new());
+
+/// The characters used to draw the lines of a board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Borders {
+    /// Heavy box-drawing characters.
+    #[default]
+    Unicode,
+    /// Only ASCII characters, for terminals and screen readers that mangle
+    /// box-drawing characters.
+    Ascii,
+}
+
+/// The colors used to tell the players' pieces apart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// Black and white pieces.
+    #[default]
+    Standard,
+    /// Blue and yellow pieces, which remain distinct with common color
+    /// vision deficiencies.
+    Colorblind,
+    /// No colors at all, with pieces marked `X` for Black and `O` for White.
+    None,
+}
+
+/// How the pieces on a board are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// A grid of cells, each showing the number of pieces on a point.
+    #[default]
+    Grid,
+    /// Columns of checkers stacking toward the center, as on a real board.
+    Stacked,
+}
+
+/// The glyphs used to draw a board.
+pub struct Glyphs {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top: [char; 3],
+    pub middle: [char; 2],
+    pub bottom: [char; 3],
+    pub empty: char,
+}
+
+/// How boards are displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    pub borders: Borders,
+    pub palette: Palette,
+    pub layout: Layout,
+}
+
+impl Theme {
+    /// Creates the default theme.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            borders: Borders::Unicode,
+            palette: Palette::Standard,
+            layout: Layout::Grid,
+        }
+    }
+
+    /// Gets the theme boards are currently displayed with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock on the current theme is poisoned.
+    #[must_use]
+    pub fn current() -> Self {
+        *CURRENT.read().expect("theme lock should not be poisoned")
+    }
+
+    /// Displays boards, and colors all other output if the palette has any,
+    /// with this theme from now on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock on the current theme is poisoned.
+    pub fn set(self) {
+        if self.palette == Palette::None {
+            colored::control::set_override(false);
+        } else {
+            colored::control::unset_override();
+        }
+        *CURRENT.write().expect("theme lock should not be poisoned") = self;
+    }
+
+    /// Loads the theme from the config file, if there is one, dropping colors
+    /// when the `NO_COLOR` environment variable is set to anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config file cannot be read or is not valid.
+    pub fn load() -> Result<Self, Error> {
+        let mut theme = match paths::config().map(fs::read_to_string) {
+            Some(Ok(config)) => Self::parse(&config)?,
+            Some(Err(error)) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(Error::InvalidConfig(error.to_string()));
+            }
+            _ => Self::new(),
+        };
+
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            theme.palette = Palette::None;
+        }
+        Ok(theme)
+    }
+
+    /// Parses a theme from a config file of `key = value` lines, where blank
+    /// lines and anything after a `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is not a known key with a valid value.
+    pub fn parse(config: &str) -> Result<Self, Error> {
+        let mut theme = Self::new();
+        for line in config.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            match line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+            {
+                Some(("borders", value)) => theme.borders = value.parse()?,
+                Some(("palette", value)) => theme.palette = value.parse()?,
+                Some(("layout", value)) => theme.layout = value.parse()?,
+                _ => return Err(Error::InvalidConfig(line.to_owned())),
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Gets the glyphs to draw a board with.
+    #[must_use]
+    pub const fn glyphs(&self) -> Glyphs {
+        match self.borders {
+            Borders::Unicode => Glyphs {
+                horizontal: '━',
+                vertical: '┃',
+                top: ['┏', '┳', '┓'],
+                middle: ['┣', '┫'],
+                bottom: ['┗', '┻', '┛'],
+                empty: '░',
+            },
+            Borders::Ascii => Glyphs {
+                horizontal: '-',
+                vertical: '|',
+                top: ['+', '+', '+'],
+                middle: ['+', '+'],
+                bottom: ['+', '+', '+'],
+                empty: '.',
+            },
+        }
+    }
+
+    /// Gets the width of a single cell of a board. Without colors, cells are
+    /// wider to fit a marker for the player.
+    #[must_use]
+    pub const fn cell_width(&self) -> usize {
+        match self.palette {
+            Palette::None => 3,
+            _ => 2,
+        }
+    }
+
+    /// Formats a cell holding the given number of the player's pieces.
+    #[must_use]
+    pub fn cell(&self, count: u8, player: Player) -> String {
+        let width = self.cell_width();
+        let text = match (count, self.palette, player) {
+            (0, ..) | (_, _, Player::None) => self.glyphs().empty.to_string().repeat(width),
+            (count, Palette::None, Player::Black) => format!("X{count:<2}"),
+            (count, Palette::None, Player::White) => format!("O{count:<2}"),
+            (count, ..) => format!("{count:02}"),
+        };
+        self.paint(&text, player).to_string()
+    }
+
+    /// Formats a cell holding the given number of the player's pieces with a
+    /// piece of their opponent pinned beneath them. Without colors, the pinned
+    /// piece is marked in lowercase after the count, otherwise the cell is
+    /// underlined.
+    #[must_use]
+    pub fn pinned_cell(&self, count: u8, player: Player) -> String {
+        match self.palette {
+            Palette::None if count < 10 => {
+                let pinned = self.checker(!player).to_lowercase();
+                format!("{}{count}{pinned}", self.checker(player))
+            }
+            Palette::None => self.cell(count, player),
+            _ => self
+                .paint(&format!("{count:02}"), player)
+                .underline()
+                .to_string(),
+        }
+    }
+
+    /// Colors the text of a cell holding pieces of the player.
+    fn paint(self, text: &str, player: Player) -> ColoredString {
+        match (self.palette, player) {
+            (Palette::None, _) => text.normal(),
+            (_, Player::None) => text.normal().dimmed(),
+            (Palette::Standard, Player::Black) => text.on_black().white().bold(),
+            (Palette::Standard, Player::White) => text.on_white().black().bold(),
+            (Palette::Colorblind, Player::Black) => text.on_blue().white().bold(),
+            (Palette::Colorblind, Player::White) => text.on_yellow().black().bold(),
+        }
+    }
+
+    /// Formats a single checker of the player, with shapes telling the players
+    /// apart even without colors.
+    #[must_use]
+    pub fn checker(&self, player: Player) -> String {
+        let plain = self.borders == Borders::Ascii || self.palette == Palette::None;
+        let glyph = match (player, plain) {
+            (Player::None, _) => " ",
+            (Player::Black, true) => "X",
+            (Player::White, true) => "O",
+            (Player::Black, false) => "●",
+            (Player::White, false) => "○",
+        };
+
+        let colored: ColoredString = match (self.palette, player) {
+            (Palette::None, _) | (_, Player::None) => glyph.normal(),
+            (Palette::Standard, _) => glyph.bold(),
+            (Palette::Colorblind, Player::Black) => glyph.blue().bold(),
+            (Palette::Colorblind, Player::White) => glyph.yellow().bold(),
+        };
+        colored.to_string()
+    }
+}
+
+impl FromStr for Borders {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unicode" => Ok(Self::Unicode),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(Error::InvalidBorders(s.to_owned())),
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(Self::Standard),
+            "colorblind" => Ok(Self::Colorblind),
+            "none" => Ok(Self::None),
+            _ => Err(Error::InvalidPalette(s.to_owned())),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "grid" => Ok(Self::Grid),
+            "stacked" => Ok(Self::Stacked),
+            _ => Err(Error::InvalidLayout(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Borders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+        })
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Standard => "standard",
+            Self::Colorblind => "colorblind",
+            Self::None => "none",
+        })
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Grid => "grid",
+            Self::Stacked => "stacked",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("ASCII".parse(), Ok(Borders::Ascii));
+        assert_eq!("colorblind".parse(), Ok(Palette::Colorblind));
+        assert_eq!(
+            "rainbow".parse::<Palette>(),
+            Err(Error::InvalidPalette("rainbow".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let config = "# accessible\nborders = ascii\n\npalette=colorblind # blue and yellow\nlayout = Stacked";
+        assert_eq!(
+            Theme::parse(config),
+            Ok(Theme {
+                borders: Borders::Ascii,
+                palette: Palette::Colorblind,
+                layout: Layout::Stacked,
+            })
+        );
+        assert_eq!(Theme::parse(""), Ok(Theme::new()));
+        assert_eq!(
+            Theme::parse("colors = on"),
+            Err(Error::InvalidConfig("colors = on".to_string()))
+        );
+        assert_eq!(
+            Theme::parse("borders = round"),
+            Err(Error::InvalidBorders("round".to_string()))
+        );
+    }
+
+    #[test]
+    fn plain_cells() {
+        let theme = Theme {
+            borders: Borders::Ascii,
+            palette: Palette::None,
+            layout: Layout::Grid,
+        };
+        assert_eq!(theme.checker(Player::White), "O");
+        assert_eq!(theme.cell(5, Player::Black), "X5 ");
+        assert_eq!(theme.cell(15, Player::White), "O15");
+        assert_eq!(theme.cell(0, Player::None), "...");
+        assert_eq!(theme.pinned_cell(2, Player::Black), "X2o");
+    }
+}
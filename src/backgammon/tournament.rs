@@ -0,0 +1,844 @@
+//! Tournaments between named players, paired by elimination brackets or by
+//! the Swiss system, kept across sessions while results come in.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{fs, io};
+
+use crate::backgammon::{Error, paths, profiles::check_name};
+
+/// The first line of a tournament file.
+const HEADER: &str = "# backgammon-cli tournament";
+/// The most opponents tried while pairing a group without rematches before
+/// rematches are allowed, as the search can otherwise take exponential time.
+const MAX_TRIES: usize = 10_000;
+
+/// How players are paired and when they are out of a tournament.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Players are out after their first loss.
+    SingleElimination,
+    /// Players are out after their second loss. Players who lose in the
+    /// winners bracket drop into a losers bracket, whose winner meets the
+    /// winner of the winners bracket in a final that is replayed if the
+    /// winner of the losers bracket wins it.
+    DoubleElimination,
+    /// Every player plays every round against players with the same number
+    /// of wins, for one round per match length.
+    Swiss,
+}
+
+impl Format {
+    /// Gets the number of losses that put a player out, if any do.
+    const fn lives(self) -> Option<usize> {
+        match self {
+            Self::SingleElimination => Some(1),
+            Self::DoubleElimination => Some(2),
+            Self::Swiss => None,
+        }
+    }
+}
+
+/// A match of a tournament round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pairing {
+    /// The round of the match, counting from one
+    pub round: usize,
+    /// The players of `Player::Black` and `Player::White` respectively
+    pub players: [String; 2],
+    /// The number of points needed to win the match
+    pub length: u32,
+    pub winner: Option<String>,
+}
+
+impl Pairing {
+    fn has(&self, player: &str) -> bool {
+        self.players.iter().any(|name| name == player)
+    }
+
+    /// Gets the opponent of a player in the match.
+    fn opponent(&self, player: &str) -> &str {
+        if self.players[0] == player {
+            &self.players[1]
+        } else {
+            &self.players[0]
+        }
+    }
+}
+
+/// A tournament with its players, in seeding order, and every pairing made
+/// so far, saved to a file after every change when loaded from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tournament {
+    pub format: Format,
+    /// The match length of each round, where rounds past the last length use
+    /// the last length
+    pub lengths: Vec<u32>,
+    pub players: Vec<String>,
+    pub pairings: Vec<Pairing>,
+    /// The round and player of each bye, which counts as a win
+    pub byes: Vec<(usize, String)>,
+    /// The file the tournament is kept in, if any
+    path: Option<PathBuf>,
+}
+
+impl Tournament {
+    /// Creates a tournament that is not kept after the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name is not valid or registered twice.
+    pub fn new(format: Format, lengths: Vec<u32>, players: &[String]) -> Result<Self, Error> {
+        let mut tournament = Self {
+            format,
+            lengths,
+            players: vec![],
+            pairings: vec![],
+            byes: vec![],
+            path: None,
+        };
+        for player in players {
+            tournament.register(player)?;
+        }
+        Ok(tournament)
+    }
+
+    /// Finds the file the tournament is kept in from
+    /// `BACKGAMMON_CLI_TOURNAMENT`, or the `backgammon-cli/tournament` file in
+    /// the XDG data directory.
+    fn file() -> Option<PathBuf> {
+        paths::data("BACKGAMMON_CLI_TOURNAMENT", "tournament")
+    }
+
+    /// Creates a tournament kept in its file, replacing any tournament there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name is not valid or registered twice.
+    pub fn create(format: Format, lengths: Vec<u32>, players: &[String]) -> Result<Self, Error> {
+        let mut tournament = Self::new(format, lengths, players)?;
+        tournament.path = Self::file();
+        Ok(tournament)
+    }
+
+    /// Loads the tournament kept in its file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no tournament or the file cannot be read
+    /// or is not valid.
+    pub fn load() -> Result<Self, Error> {
+        let path = Self::file().ok_or(Error::NoTournament)?;
+        let mut tournament = match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Err(Error::NoTournament);
+            }
+            Err(error) => return Err(Error::TournamentFailed(error.to_string())),
+        };
+        tournament.path = Some(path);
+        Ok(tournament)
+    }
+
+    /// Parses a tournament from a `format <format>` line, a `lengths
+    /// <length>...` line, `player <name>` lines in seeding order, `match
+    /// <round> <black> <white> <length> <winner>` lines with a winner of `-`
+    /// while unplayed, and `bye <round> <name>` lines, ignoring blank lines
+    /// and comments starting with `#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is not valid, names a player who is not
+    /// registered or pairs a player with themselves, or the format is missing.
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let mut format = None;
+        let mut tournament = Self::new(Format::Swiss, vec![], &[])?;
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidTournament(line.to_owned());
+            let number = |word: &str| word.parse::<u32>().map_err(|_| invalid());
+            let round = |word: &str| word.parse::<usize>().map_err(|_| invalid());
+            let words: Vec<_> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["format", name] => format = Some(name.parse()?),
+                ["lengths", lengths @ ..] => {
+                    tournament.lengths = lengths
+                        .iter()
+                        .map(|length| number(length))
+                        .collect::<Result<_, _>>()?;
+                }
+                ["player", name] => tournament.register(name).map_err(|_| invalid())?,
+                ["match", index, black, white, length, winner] => {
+                    let players = [(*black).to_owned(), (*white).to_owned()];
+                    if black == white
+                        || !players
+                            .iter()
+                            .all(|player| tournament.players.contains(player))
+                    {
+                        return Err(invalid());
+                    }
+                    let winner = match *winner {
+                        "-" => None,
+                        winner if players.iter().any(|player| player == winner) => {
+                            Some(winner.to_owned())
+                        }
+                        _ => return Err(invalid()),
+                    };
+                    tournament.pairings.push(Pairing {
+                        round: round(index)?,
+                        players,
+                        length: number(length)?,
+                        winner,
+                    });
+                }
+                ["bye", index, name] if tournament.players.iter().any(|player| player == name) => {
+                    tournament.byes.push((round(index)?, (*name).to_owned()));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        tournament.format = format.ok_or_else(|| Error::InvalidTournament("format".to_owned()))?;
+        Ok(tournament)
+    }
+
+    /// Adds a player as the lowest seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not valid or already registered, or the
+    /// first round has been paired.
+    pub fn register(&mut self, name: &str) -> Result<(), Error> {
+        check_name(name)?;
+        if self.round() > 0 {
+            return Err(Error::TournamentStarted);
+        }
+        if self.players.iter().any(|player| player == name) {
+            return Err(Error::DuplicatePlayer(name.to_owned()));
+        }
+        self.players.push(name.to_owned());
+        Ok(())
+    }
+
+    /// Gets the number of the latest round paired, which is 0 before the
+    /// first round.
+    #[must_use]
+    pub fn round(&self) -> usize {
+        let pairings = self.pairings.iter().map(|pairing| pairing.round);
+        let byes = self.byes.iter().map(|(round, _)| *round);
+        pairings.chain(byes).max().unwrap_or(0)
+    }
+
+    /// Gets the match length of a round.
+    fn length(&self, round: usize) -> u32 {
+        let index = round
+            .saturating_sub(1)
+            .min(self.lengths.len().saturating_sub(1));
+        self.lengths.get(index).copied().unwrap_or(1)
+    }
+
+    /// Gets the matches of the latest round that have not been played.
+    pub fn pending(&self) -> impl Iterator<Item = &Pairing> {
+        let round = self.round();
+        self.pairings
+            .iter()
+            .filter(move |pairing| pairing.round == round && pairing.winner.is_none())
+    }
+
+    /// Gets the number of matches and byes a player has won.
+    #[must_use]
+    pub fn wins(&self, player: &str) -> usize {
+        let matches = self
+            .pairings
+            .iter()
+            .filter(|pairing| pairing.winner.as_deref() == Some(player))
+            .count();
+        matches + self.byes.iter().filter(|(_, name)| name == player).count()
+    }
+
+    /// Gets the number of matches a player has lost.
+    #[must_use]
+    pub fn losses(&self, player: &str) -> usize {
+        self.pairings
+            .iter()
+            .filter(|pairing| pairing.has(player))
+            .filter(|pairing| {
+                pairing
+                    .winner
+                    .as_deref()
+                    .is_some_and(|winner| winner != player)
+            })
+            .count()
+    }
+
+    /// Gets the sum of the wins of every opponent a player has met, which
+    /// breaks ties in a Swiss tournament.
+    #[must_use]
+    pub fn buchholz(&self, player: &str) -> usize {
+        self.pairings
+            .iter()
+            .filter(|pairing| pairing.has(player))
+            .map(|pairing| self.wins(pairing.opponent(player)))
+            .sum()
+    }
+
+    /// Gets the players still in an elimination tournament, in seeding order,
+    /// or every player of a Swiss tournament.
+    fn remaining(&self) -> Vec<&str> {
+        self.players
+            .iter()
+            .map(String::as_str)
+            .filter(|player| {
+                self.format
+                    .lives()
+                    .is_none_or(|lives| self.losses(player) < lives)
+            })
+            .collect()
+    }
+
+    /// Checks whether every round has been played.
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        if self.pending().next().is_some() {
+            return false;
+        }
+        match self.format {
+            Format::Swiss => self.round() >= self.lengths.len(),
+            _ => self.round() > 0 && self.remaining().len() < 2,
+        }
+    }
+
+    /// Gets the winner of a finished tournament, which for a Swiss tournament
+    /// is the player with the most wins and then the best tiebreak.
+    #[must_use]
+    pub fn champion(&self) -> Option<&str> {
+        self.is_over()
+            .then(|| self.standings().first().copied())
+            .flatten()
+    }
+
+    /// Gets the players from first place to last.
+    #[must_use]
+    pub fn standings(&self) -> Vec<&str> {
+        let mut players: Vec<_> = self.players.iter().map(String::as_str).collect();
+        match self.format {
+            Format::Swiss => players.sort_by_key(|player| {
+                std::cmp::Reverse((self.wins(player), self.buchholz(player)))
+            }),
+            _ => players
+                .sort_by_key(|player| (self.losses(player), std::cmp::Reverse(self.wins(player)))),
+        }
+        players
+    }
+
+    /// Pairs the next round, giving a bye to one player of any group with an
+    /// odd number of players.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are too few players, the latest round has
+    /// matches to play, or the tournament is over.
+    pub fn pair(&mut self) -> Result<usize, Error> {
+        if self.players.len() < 2 || self.lengths.is_empty() {
+            return Err(Error::TooFewPlayers);
+        }
+        if self.pending().next().is_some() {
+            return Err(Error::PendingMatches(self.round()));
+        }
+        if self.is_over() {
+            return Err(Error::TournamentOver);
+        }
+
+        let round = self.round() + 1;
+        let remaining = self.remaining();
+        let mut pairings = vec![];
+        let groups = match self.format {
+            Format::SingleElimination => vec![remaining],
+            Format::DoubleElimination if remaining.len() == 2 => vec![remaining],
+            Format::DoubleElimination => {
+                let (unbeaten, beaten): (Vec<_>, Vec<_>) = remaining
+                    .into_iter()
+                    .partition(|player| self.losses(player) == 0);
+                let (survivors, dropped): (Vec<_>, Vec<_>) =
+                    beaten.into_iter().partition(|player| self.survived(player));
+
+                // The losers bracket alternates between rounds among the
+                // players surviving in it and rounds against the players who
+                // have just dropped into it.
+                let losers = if survivors.len() > dropped.len() {
+                    survivors
+                } else {
+                    let (drop_ins, left) = self.drop_in(&survivors, dropped);
+                    pairings.extend(drop_ins);
+                    left
+                };
+                vec![unbeaten, losers]
+            }
+            Format::Swiss => vec![self.standings()],
+        };
+
+        // A player alone in a bracket waits for the other bracket.
+        let mut byes = vec![];
+        for group in groups.into_iter().filter(|group| group.len() > 1) {
+            let (group_pairings, bye) = self.pair_group(group);
+            pairings.extend(group_pairings);
+            byes.extend(bye);
+        }
+
+        let length = self.length(round);
+        self.pairings
+            .extend(pairings.into_iter().map(|players| Pairing {
+                round,
+                players,
+                length,
+                winner: None,
+            }));
+        self.byes
+            .extend(byes.into_iter().map(|player| (round, player)));
+        Ok(round)
+    }
+
+    /// Pairs a group of players, avoiding rematches where possible. Swiss
+    /// groups are in ranking order and paired with their neighbours, with the
+    /// bye going to the lowest ranked player, while elimination groups are in
+    /// seeding order and paired from both ends, with the bye going to the
+    /// highest seed. Byes go to players with the fewest byes.
+    fn pair_group(&self, mut group: Vec<&str>) -> (Vec<[String; 2]>, Option<String>) {
+        let swiss = self.format == Format::Swiss;
+        let byes = |player: &str| self.byes.iter().filter(|(_, name)| name == player).count();
+
+        let bye = (group.len() % 2 == 1).then(|| {
+            let order = group.iter().enumerate();
+            let (index, _) = if swiss {
+                order.rev().min_by_key(|(_, player)| byes(player))
+            } else {
+                order.min_by_key(|(_, player)| byes(player))
+            }
+            .unwrap_or((0, &""));
+            group.remove(index).to_owned()
+        });
+
+        let mut tries = MAX_TRIES;
+        let pairings = self
+            .match_up(&group, false, &mut tries)
+            .or_else(|| {
+                tries = MAX_TRIES;
+                self.match_up(&group, true, &mut tries)
+            })
+            .unwrap_or_default();
+        (pairings, bye)
+    }
+
+    /// Pairs the first player of a group with the first opponent, in order of
+    /// preference, that lets the rest of the group be paired, allowing
+    /// rematches only if told to. Gives up once the number of opponents tried
+    /// reaches the given limit.
+    fn match_up(
+        &self,
+        group: &[&str],
+        rematches: bool,
+        tries: &mut usize,
+    ) -> Option<Vec<[String; 2]>> {
+        let Some((player, rest)) = group.split_first() else {
+            return Some(vec![]);
+        };
+        let mut candidates: Vec<_> = (0..rest.len()).collect();
+        if self.format != Format::Swiss {
+            candidates.reverse();
+        }
+
+        candidates.into_iter().find_map(|index| {
+            if *tries == 0 || (!rematches && self.met(player, rest[index])) {
+                return None;
+            }
+            *tries -= 1;
+            let mut others = rest.to_vec();
+            let opponent = others.remove(index);
+            let mut pairings = self.match_up(&others, rematches, tries)?;
+            pairings.insert(0, [(*player).to_owned(), opponent.to_owned()]);
+            Some(pairings)
+        })
+    }
+
+    /// Checks whether a player has won a match or bye since their latest
+    /// loss, rather than having just dropped into the losers bracket.
+    fn survived(&self, player: &str) -> bool {
+        let lost = self
+            .pairings
+            .iter()
+            .filter(|pairing| pairing.has(player))
+            .filter(|pairing| {
+                pairing
+                    .winner
+                    .as_deref()
+                    .is_some_and(|winner| winner != player)
+            })
+            .map(|pairing| pairing.round)
+            .max()
+            .unwrap_or(0);
+        let matches = self
+            .pairings
+            .iter()
+            .filter(|pairing| pairing.winner.as_deref() == Some(player))
+            .map(|pairing| pairing.round);
+        let byes = self
+            .byes
+            .iter()
+            .filter(|(_, name)| name == player)
+            .map(|(round, _)| *round);
+        matches.chain(byes).any(|round| round > lost)
+    }
+
+    /// Pairs each player surviving in the losers bracket, in seeding order,
+    /// with the lowest seed of the players who have just dropped into it that
+    /// they have not met, returning the pairings and the players left over.
+    fn drop_in<'a>(
+        &self,
+        survivors: &[&str],
+        mut dropped: Vec<&'a str>,
+    ) -> (Vec<[String; 2]>, Vec<&'a str>) {
+        let mut pairings = vec![];
+        for survivor in survivors {
+            let index = dropped
+                .iter()
+                .rposition(|player| !self.met(survivor, player))
+                .unwrap_or(dropped.len() - 1);
+            let opponent = dropped.remove(index);
+            pairings.push([(*survivor).to_owned(), opponent.to_owned()]);
+        }
+        (pairings, dropped)
+    }
+
+    /// Checks whether two players have been paired before.
+    fn met(&self, a: &str, b: &str) -> bool {
+        self.pairings
+            .iter()
+            .any(|pairing| pairing.has(a) && pairing.has(b))
+    }
+
+    /// Records the result of a match of the latest round, returning its
+    /// length.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the players have no match left to play.
+    pub fn record(&mut self, winner: &str, loser: &str) -> Result<u32, Error> {
+        let round = self.round();
+        let pairing = self
+            .pairings
+            .iter_mut()
+            .find(|pairing| {
+                pairing.round == round
+                    && pairing.winner.is_none()
+                    && pairing.has(winner)
+                    && pairing.has(loser)
+                    && winner != loser
+            })
+            .ok_or_else(|| Error::UnknownMatch(winner.to_owned(), loser.to_owned()))?;
+        pairing.winner = Some(winner.to_owned());
+        Ok(pairing.length)
+    }
+
+    /// Saves the tournament to its file, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let failed = |error: io::Error| Error::TournamentFailed(error.to_string());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(failed)?;
+        }
+        fs::write(path, self.serialize()).map_err(failed)
+    }
+
+    /// Writes the tournament in the format read by [`Tournament::parse`].
+    fn serialize(&self) -> String {
+        let mut lines = vec![
+            HEADER.to_string(),
+            format!("format {}", self.format),
+            format!(
+                "lengths {}",
+                self.lengths
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        ];
+        lines.extend(self.players.iter().map(|player| format!("player {player}")));
+        lines.extend(self.pairings.iter().map(|pairing| {
+            let [black, white] = &pairing.players;
+            let winner = pairing.winner.as_deref().unwrap_or("-");
+            format!(
+                "match {} {black} {white} {} {winner}",
+                pairing.round, pairing.length
+            )
+        }));
+        lines.extend(
+            self.byes
+                .iter()
+                .map(|(round, player)| format!("bye {round} {player}")),
+        );
+        lines.join("\n") + "\n"
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "single" => Ok(Self::SingleElimination),
+            "double" => Ok(Self::DoubleElimination),
+            "swiss" => Ok(Self::Swiss),
+            _ => Err(Error::InvalidFormat(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::SingleElimination => "single",
+            Self::DoubleElimination => "double",
+            Self::Swiss => "swiss",
+        })
+    }
+}
+
+impl Display for Tournament {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = match self.format {
+            Format::SingleElimination => "Single elimination",
+            Format::DoubleElimination => "Double elimination",
+            Format::Swiss => "Swiss",
+        };
+        write!(f, "{format} tournament, round {}", self.round())?;
+        if let Some(champion) = self.champion() {
+            write!(f, ", won by {champion}")?;
+        }
+
+        let width = self.players.iter().map(String::len).max().unwrap_or(0);
+        for (rank, player) in self.standings().into_iter().enumerate() {
+            let record = format!("{}-{}", self.wins(player), self.losses(player));
+            write!(f, "\n  {:>3}. {player:<width$} {record:>5}", rank + 1)?;
+            match self.format.lives() {
+                None => write!(f, "  buchholz {}", self.buchholz(player))?,
+                Some(lives) if self.losses(player) >= lives => write!(f, "  out")?,
+                Some(_) => {}
+            }
+        }
+
+        let round = self.round();
+        if round > 0 {
+            write!(f, "\n\nRound {round}")?;
+        }
+        for pairing in self
+            .pairings
+            .iter()
+            .filter(|pairing| pairing.round == round)
+        {
+            let [black, white] = &pairing.players;
+            let result = pairing.winner.as_ref().map_or_else(
+                || "to play".to_string(),
+                |winner| format!("won by {winner}"),
+            );
+            write!(
+                f,
+                "\n  {black} vs {white}, {} point match, {result}",
+                pairing.length
+            )?;
+        }
+        for (_, player) in self.byes.iter().filter(|(bye, _)| *bye == round) {
+            write!(f, "\n  {player} has a bye")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    /// Plays out every round, with the higher seed winning each match.
+    fn play_out(tournament: &mut Tournament) {
+        loop {
+            let pending: Vec<_> = tournament.pending().cloned().collect();
+            for pairing in pending {
+                let seed =
+                    |name: &String| tournament.players.iter().position(|player| player == name);
+                let [a, b] = &pairing.players;
+                let (winner, loser) = if seed(a) < seed(b) { (a, b) } else { (b, a) };
+                tournament.record(winner, loser).unwrap();
+            }
+            if tournament.pair().is_err() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn single_elimination() {
+        let players = names(&["a", "b", "c", "d", "e"]);
+        let mut tournament =
+            Tournament::new(Format::SingleElimination, vec![3, 5], &players).unwrap();
+
+        assert_eq!(tournament.pair(), Ok(1));
+        assert_eq!(tournament.byes, [(1, "a".to_string())]);
+        let pairings: Vec<_> = tournament
+            .pending()
+            .map(|pairing| pairing.players.join(" vs "))
+            .collect();
+        assert_eq!(pairings, ["b vs e", "c vs d"]);
+        assert_eq!(tournament.pair(), Err(Error::PendingMatches(1)));
+        assert_eq!(tournament.register("f"), Err(Error::TournamentStarted));
+
+        play_out(&mut tournament);
+        assert_eq!(tournament.champion(), Some("a"));
+        assert_eq!(tournament.pairings.last().unwrap().length, 5);
+        assert_eq!(tournament.losses("e"), 1);
+        assert_eq!(tournament.pair(), Err(Error::TournamentOver));
+    }
+
+    #[test]
+    fn double_elimination() {
+        let players = names(&["a", "b", "c", "d"]);
+        let mut tournament = Tournament::new(Format::DoubleElimination, vec![1], &players).unwrap();
+        let mut round = |results: &[(&str, &str)]| {
+            let round = tournament.pair().unwrap();
+            let pairings: Vec<_> = tournament
+                .pending()
+                .map(|pairing| pairing.players.join(" vs "))
+                .collect();
+            for (winner, loser) in results {
+                tournament.record(winner, loser).unwrap();
+            }
+            (round, pairings)
+        };
+
+        assert_eq!(round(&[("d", "a"), ("b", "c")]).1, ["a vs d", "b vs c"]);
+        // The winners bracket plays on while the players who lost in it meet
+        // in the losers bracket.
+        assert_eq!(round(&[("b", "d"), ("a", "c")]).1, ["b vs d", "a vs c"]);
+        // The winner of the losers bracket meets the player who just dropped
+        // into it, while the winner of the winners bracket waits.
+        assert_eq!(round(&[("a", "d")]).1, ["a vs d"]);
+        // The final is replayed as its winner had not lost before.
+        assert_eq!(round(&[("a", "b")]).1, ["a vs b"]);
+        assert_eq!(round(&[("a", "b")]), (5, vec!["a vs b".to_string()]));
+
+        assert!(tournament.byes.is_empty());
+        assert_eq!(tournament.champion(), Some("a"));
+        assert_eq!(tournament.losses("b"), 2);
+        assert_eq!(tournament.pair(), Err(Error::TournamentOver));
+    }
+
+    #[test]
+    fn double_elimination_final() {
+        let players = names(&["a", "b", "c", "d", "e", "f"]);
+        let mut tournament = Tournament::new(Format::DoubleElimination, vec![1], &players).unwrap();
+        play_out(&mut tournament);
+
+        // The winner of the winners bracket wins the final without a replay.
+        assert_eq!(tournament.champion(), Some("a"));
+        assert_eq!(tournament.losses("a"), 0);
+        assert_eq!(tournament.losses("b"), 2);
+        let [.., last] = &tournament.pairings[..] else {
+            unreachable!()
+        };
+        assert_eq!(last.players, ["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn swiss() {
+        let players = names(&["a", "b", "c", "d", "e", "f"]);
+        let mut tournament = Tournament::new(Format::Swiss, vec![3, 3, 5], &players).unwrap();
+        play_out(&mut tournament);
+
+        assert_eq!(tournament.round(), 3);
+        assert!(tournament.is_over());
+        assert_eq!(tournament.champion(), Some("a"));
+        assert_eq!(tournament.wins("a"), 3);
+        for pairing in &tournament.pairings {
+            let meetings = tournament
+                .pairings
+                .iter()
+                .filter(|other| other.has(&pairing.players[0]) && other.has(&pairing.players[1]))
+                .count();
+            assert_eq!(meetings, 1);
+        }
+    }
+
+    #[test]
+    fn rematches_when_stuck() {
+        // Only "w" is left for both "x" and "y" to play without a rematch, so
+        // there is no pairing without one, which a full search only finds out
+        // after trying every pairing of the others.
+        let players: Vec<_> = (0..24).map(|index| format!("p{index:02}")).collect();
+        let mut tournament = Tournament::new(Format::Swiss, vec![1], &players).unwrap();
+        let [.., w, x, y] = &players[..] else {
+            unreachable!()
+        };
+        for player in &players[..21] {
+            for opponent in [x, y] {
+                tournament.pairings.push(Pairing {
+                    round: 1,
+                    players: [player.clone(), opponent.clone()],
+                    length: 1,
+                    winner: Some(player.clone()),
+                });
+            }
+        }
+        tournament.pairings.push(Pairing {
+            round: 1,
+            players: [x.clone(), y.clone()],
+            length: 1,
+            winner: Some(x.clone()),
+        });
+
+        let group: Vec<_> = players.iter().map(String::as_str).collect();
+        let (pairings, bye) = tournament.pair_group(group);
+        assert_eq!(bye, None);
+        assert_eq!(pairings.len(), 12);
+        assert!(pairings.iter().any(|pairing| pairing.contains(w)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let players = names(&["a", "b", "c"]);
+        let mut tournament = Tournament::new(Format::Swiss, vec![5, 7], &players).unwrap();
+        tournament.pair().unwrap();
+        tournament.record("a", "b").unwrap();
+
+        assert_eq!(Tournament::parse(&tournament.serialize()), Ok(tournament));
+        assert_eq!(
+            Tournament::parse("format knockout"),
+            Err(Error::InvalidFormat("knockout".to_string()))
+        );
+        assert_eq!(
+            Tournament::parse("lengths 1"),
+            Err(Error::InvalidTournament("format".to_string()))
+        );
+        for line in ["match 1 a z 1 -", "match 1 a a 1 -"] {
+            assert_eq!(
+                Tournament::parse(&format!("format swiss\nplayer a\n{line}")),
+                Err(Error::InvalidTournament(line.to_string()))
+            );
+        }
+        assert_eq!(
+            Tournament::new(Format::Swiss, vec![1], &names(&["a", "a"])),
+            Err(Error::DuplicatePlayer("a".to_string()))
+        );
+    }
+}
@@ -0,0 +1,612 @@
+//! Training drills asking for the best play or cube action in a position,
+//! with the accuracy of each category of drill kept across sessions.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+use colored::Colorize;
+use rand::seq::SliceRandom;
+
+use crate::backgammon::{
+    Error,
+    analysis::DOUBTFUL,
+    board::Board,
+    book::Book,
+    compact::CompactBoard,
+    dice_roll::DiceRoll,
+    game::{Game, read_line},
+    paths,
+    player::Player,
+};
+
+/// The first line of a training progress file.
+const HEADER: &str = "# backgammon-cli training";
+/// The number of recent sessions shown for each category.
+const RECENT: usize = 5;
+
+/// The kind of position a drill is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Opening,
+    Bearoff,
+    PrimeVsPrime,
+    BackGame,
+}
+
+impl Category {
+    const ALL: [Self; 4] = [
+        Self::Opening,
+        Self::Bearoff,
+        Self::PrimeVsPrime,
+        Self::BackGame,
+    ];
+}
+
+/// What a drill asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Question {
+    /// The best play of the roll
+    Play([u8; 2]),
+    /// The best cube action
+    Cube,
+}
+
+/// A possible answer to a drill.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    /// A play in standard notation or a cube action such as `double/take`
+    pub answer: String,
+    /// The equity lost by giving this answer instead of the best one
+    pub loss: f64,
+}
+
+/// A position with `Player::Black` on roll and a question about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drill {
+    pub category: Category,
+    pub position_id: String,
+    pub question: Question,
+    /// The known answers from best to worst
+    pub answers: Vec<Answer>,
+}
+
+/// The built-in drills of the categories other than openings, in the format
+/// read by [`Drill::read`], with their answers and losses reviewed by hand.
+const DRILLS: &str = "\
+bearoff 7N4DADC2uwMAAA 6-3: 6/off 5/2 = 0, 6/off 6/3 = 0.04, 6/off 3/off = 0.05
+bearoff 3N0BAKCdBQAAAA 6-1: 6/off 1/off = 0, 6/off 2/1 = 0.01, 6/off 3/2 = 0.01, 6/off 5/4 = 0.03
+bearoff AQAAGAAAAAAAAA cube: double/take = 0, double/pass = 0.11, no double = 0.44
+prime sNuGATDYboMBMA 6-5: 13/7 13/8 = 0, 13/8 8/2 = 0.2, 8/2 7/2 = 0.25
+backgame 5u45AADgGXMAMw 4-2: 8/4 6/4 = 0, 13/9 11/9 = 0.03, 13/11 13/9 = 0.04";
+
+impl Drill {
+    /// Creates the built-in drills: the opening rolls answered from the
+    /// built-in opening book, and positions of the other categories with
+    /// stored answers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a built-in position is not valid.
+    #[must_use]
+    pub fn builtin() -> Vec<Self> {
+        let book = Book::builtin();
+        let mut drills = vec![];
+
+        for roll in (1..=6).flat_map(|a| (1..a).map(move |b| [a, b])) {
+            let game = Game::from(Player::Black, DiceRoll::from(roll), Board::new());
+            let lines: Vec<_> = book
+                .lines(&game)
+                .iter()
+                .filter_map(|line| Some((line.play.clone(), line.equity?)))
+                .collect();
+            let best = lines.first().map_or(0.0, |&(_, equity)| equity);
+            drills.push(Self {
+                category: Category::Opening,
+                position_id: CompactBoard::from(&game.board).position_id(Player::Black),
+                question: Question::Play(roll),
+                answers: lines
+                    .into_iter()
+                    .map(|(answer, equity)| Answer {
+                        answer,
+                        loss: best - equity,
+                    })
+                    .collect(),
+            });
+        }
+
+        drills.extend(
+            DRILLS
+                .lines()
+                .map(|line| Self::parse(line).expect("built-in drill should be valid")),
+        );
+        drills
+    }
+
+    /// Reads drills from a file of lines of `<category> <position id> <roll>:
+    /// <answer> = <loss>, ...`, where the roll is `cube` for a cube action and
+    /// each answer is a play in standard notation or a cube action with the
+    /// equity it loses, ignoring blank lines and comments starting with `#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is not valid.
+    pub fn read(path: &Path) -> Result<Vec<Self>, Error> {
+        let contents =
+            fs::read_to_string(path).map_err(|error| Error::TrainingFailed(error.to_string()))?;
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Self::parse)
+            .collect()
+    }
+
+    /// Parses a single drill in the format read by [`Drill::read`].
+    fn parse(line: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidDrill(line.to_owned());
+        let (head, answers) = line.split_once(':').ok_or_else(invalid)?;
+        let [category, position_id, question] = head
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| invalid())?;
+
+        let question = match question.split_once('-') {
+            None if question == "cube" => Question::Cube,
+            Some((a, b)) => Question::Play([
+                a.parse().map_err(|_| invalid())?,
+                b.parse().map_err(|_| invalid())?,
+            ]),
+            None => return Err(invalid()),
+        };
+        let dice = match question {
+            Question::Play(dice) if dice.iter().all(|die| (1..=6).contains(die)) => dice,
+            Question::Play(_) => return Err(invalid()),
+            Question::Cube => [1, 1],
+        };
+        Game::from_position_id(position_id, dice).map_err(|_| invalid())?;
+
+        let mut answers = answers
+            .split(',')
+            .map(|answer| {
+                let (answer, loss) = answer.rsplit_once('=').ok_or_else(invalid)?;
+                Ok(Answer {
+                    answer: normalize(answer),
+                    loss: loss.trim().parse().map_err(|_| invalid())?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        answers.sort_by(|a, b| a.loss.total_cmp(&b.loss));
+
+        Ok(Self {
+            category: category.parse()?,
+            position_id: position_id.to_owned(),
+            question,
+            answers,
+        })
+    }
+
+    /// Gets the game of the drill, with the roll of a play question.
+    fn game(&self) -> Game {
+        let dice = match self.question {
+            Question::Play(dice) => dice,
+            Question::Cube => [1, 1],
+        };
+        Game::from_position_id(&self.position_id, dice).expect("drill should be valid")
+    }
+
+    /// Finds the answer matching a response, which for a play is any answer
+    /// reaching the same position. Returns nothing if the response is valid
+    /// but not a known answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the play is not legal or the cube action is not
+    /// one of the answers.
+    fn answer(&self, response: &str) -> Result<Option<&Answer>, Error> {
+        match self.question {
+            Question::Cube => {
+                let response = normalize(response);
+                self.answers
+                    .iter()
+                    .find(|answer| answer.answer == response)
+                    .map(Some)
+                    .ok_or(Error::InvalidCubeAction(response))
+            }
+            Question::Play(_) => {
+                let game = self.game();
+                let position = |notation: &str| {
+                    let turn = game.read_turn(notation)?;
+                    let mut after = game.clone();
+                    after.take_turn(&turn);
+                    Ok::<_, Error>(CompactBoard::from(&after.board))
+                };
+                let played = position(response)?;
+                Ok(self
+                    .answers
+                    .iter()
+                    .find(|answer| position(&answer.answer).is_ok_and(|board| board == played)))
+            }
+        }
+    }
+}
+
+/// Lowercases an answer and collapses its whitespace so cube actions match
+/// however they are typed.
+fn normalize(answer: &str) -> String {
+    answer
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// The drills answered in a category.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Score {
+    pub attempts: u32,
+    /// The answers losing less than a doubtful turn
+    pub correct: u32,
+    /// The answers with a known loss
+    pub scored: u32,
+    /// The total equity lost by the answers with a known loss
+    pub loss: f64,
+}
+
+impl Score {
+    /// Gets the share of correct answers as a percentage.
+    fn accuracy(&self) -> f64 {
+        f64::from(self.correct) * 100.0 / f64::from(self.attempts.max(1))
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.attempts += other.attempts;
+        self.correct += other.correct;
+        self.scored += other.scored;
+        self.loss += other.loss;
+    }
+}
+
+/// The scores of every training session by category, saved to a file after
+/// every drill when loaded from one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    sessions: Vec<[Score; 4]>,
+    /// The file the progress is kept in, if any
+    path: Option<PathBuf>,
+}
+
+impl Progress {
+    /// Creates empty progress that is not kept after the session.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            sessions: vec![],
+            path: None,
+        }
+    }
+
+    /// Loads the progress kept in the file from `BACKGAMMON_CLI_TRAINING`, or
+    /// the `backgammon-cli/training` file in the XDG data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or is not valid.
+    pub fn load() -> Result<Self, Error> {
+        let Some(path) = paths::data("BACKGAMMON_CLI_TRAINING", "training") else {
+            return Ok(Self::new());
+        };
+        let mut progress = match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::new(),
+            Err(error) => return Err(Error::TrainingFailed(error.to_string())),
+        };
+        progress.path = Some(path);
+        Ok(progress)
+    }
+
+    /// Parses progress from `session` lines, each followed by lines of
+    /// `<category> <attempts> <correct> <scored> <loss>`, ignoring blank lines
+    /// and comments starting with `#`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is not valid.
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let mut progress = Self::new();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidProgress(line.to_owned());
+            let number = |word: &str| word.parse::<u32>().map_err(|_| invalid());
+            let words: Vec<_> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["session"] => progress.sessions.push(<[Score; 4]>::default()),
+                [category, attempts, correct, scored, loss] => {
+                    let category = category.parse::<Category>().map_err(|_| invalid())?;
+                    let session = progress.sessions.last_mut().ok_or_else(invalid)?;
+                    session[category as usize] = Score {
+                        attempts: number(attempts)?,
+                        correct: number(correct)?,
+                        scored: number(scored)?,
+                        loss: loss.parse().map_err(|_| invalid())?,
+                    };
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(progress)
+    }
+
+    /// Starts a new session.
+    pub fn start_session(&mut self) {
+        self.sessions.push(<[Score; 4]>::default());
+    }
+
+    /// Counts an answer in the latest session, with the equity it lost if
+    /// known.
+    pub fn record(&mut self, category: Category, loss: Option<f64>) {
+        if self.sessions.is_empty() {
+            self.start_session();
+        }
+        let sessions = self.sessions.len();
+        let score = &mut self.sessions[sessions - 1][category as usize];
+        score.attempts += 1;
+        if let Some(loss) = loss {
+            score.correct += u32::from(loss < DOUBTFUL);
+            score.scored += 1;
+            score.loss += loss;
+        }
+    }
+
+    /// Gets the score of a category over every session.
+    #[must_use]
+    pub fn total(&self, category: Category) -> Score {
+        self.sessions
+            .iter()
+            .fold(Score::default(), |mut total, session| {
+                total.add(&session[category as usize]);
+                total
+            })
+    }
+
+    /// Saves the progress to the file it was loaded from, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let failed = |error: io::Error| Error::TrainingFailed(error.to_string());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(failed)?;
+        }
+        fs::write(path, self.serialize()).map_err(failed)
+    }
+
+    /// Writes the progress in the format read by [`Progress::parse`].
+    fn serialize(&self) -> String {
+        let mut lines = vec![HEADER.to_string()];
+        for session in &self.sessions {
+            lines.push("session".to_string());
+            lines.extend(
+                Category::ALL
+                    .iter()
+                    .zip(session)
+                    .filter(|(_, score)| score.attempts > 0)
+                    .map(|(category, score)| {
+                        format!(
+                            "{category} {} {} {} {}",
+                            score.attempts, score.correct, score.scored, score.loss
+                        )
+                    }),
+            );
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Runs a training session of the drills in a random order until they are
+/// all answered or input ends, recording each answer in the progress.
+pub fn train(mut drills: Vec<Drill>, progress: &mut Progress) {
+    drills.shuffle(&mut rand::rng());
+    progress.start_session();
+
+    for (index, drill) in drills.iter().enumerate() {
+        let game = drill.game();
+        println!("\n{game}\n");
+        let prompt = match drill.question {
+            Question::Play([a, b]) => format!(
+                "Drill {} of {} ({}): Black to play {a}-{b}: ",
+                index + 1,
+                drills.len(),
+                drill.category
+            ),
+            Question::Cube => format!(
+                "Drill {} of {} ({}): Black's cube action ({}): ",
+                index + 1,
+                drills.len(),
+                drill.category,
+                drill
+                    .answers
+                    .iter()
+                    .map(|answer| answer.answer.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        let answer = loop {
+            let Ok(input) = read_line(&prompt) else {
+                return;
+            };
+            match input.trim() {
+                "skip" => break None,
+                "quit" => return,
+                input => match drill.answer(input) {
+                    Ok(answer) => break Some(answer),
+                    Err(error) => println!("{}", error.to_string().red().bold()),
+                },
+            }
+        };
+        let Some(answer) = answer else {
+            continue;
+        };
+
+        let loss = answer.map(|answer| answer.loss);
+        match loss {
+            Some(loss) if loss < DOUBTFUL => println!("{}", "Correct".green().bold()),
+            Some(loss) => println!("{}", format!("Lost {loss:.3}").red().bold()),
+            None => println!("{}", "Not one of the known answers".red().bold()),
+        }
+        for answer in drill.answers.iter().take(3) {
+            println!("  {:<24} {:.3}", answer.answer, answer.loss);
+        }
+
+        progress.record(drill.category, loss);
+        if let Err(error) = progress.save() {
+            println!("{}", error.to_string().red().bold());
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "opening" => Ok(Self::Opening),
+            "bearoff" => Ok(Self::Bearoff),
+            "prime" => Ok(Self::PrimeVsPrime),
+            "backgame" => Ok(Self::BackGame),
+            _ => Err(Error::InvalidCategory(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Opening => "opening",
+            Self::Bearoff => "bearoff",
+            Self::PrimeVsPrime => "prime",
+            Self::BackGame => "backgame",
+        })
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Training")?;
+        for category in Category::ALL {
+            let total = self.total(category);
+            if total.attempts == 0 {
+                write!(f, "\n  {category:<9} no drills answered")?;
+                continue;
+            }
+
+            let average = total.loss / f64::from(total.scored.max(1));
+            let recent: Vec<_> = self
+                .sessions
+                .iter()
+                .map(|session| session[category as usize])
+                .filter(|score| score.attempts > 0)
+                .collect();
+            let recent = recent[recent.len().saturating_sub(RECENT)..]
+                .iter()
+                .map(|score| format!("{:.0}%", score.accuracy()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            write!(
+                f,
+                "\n  {category:<9} {} {}, {:.0}% correct, average loss {average:.3}, recent sessions {recent}",
+                total.attempts,
+                if total.attempts == 1 {
+                    "drill"
+                } else {
+                    "drills"
+                },
+                total.accuracy(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin() {
+        let drills = Drill::builtin();
+        assert_eq!(drills.len(), 15 + DRILLS.lines().count());
+        for drill in &drills {
+            assert!(drill.answers[0].loss.abs() < f64::EPSILON);
+            for answer in &drill.answers {
+                assert_eq!(drill.answer(&answer.answer), Ok(Some(answer)));
+            }
+        }
+        assert!(drills.iter().any(|drill| drill.question == Question::Cube));
+
+        let opening = drills
+            .iter()
+            .find(|drill| drill.question == Question::Play([3, 1]))
+            .unwrap();
+        assert_eq!(
+            opening.answer("6/5 8/5").unwrap().unwrap().answer,
+            "8/5 6/5"
+        );
+        assert!(opening.answer("24/18").is_err());
+        assert_eq!(opening.answer("24/21 24/23"), Ok(None));
+    }
+
+    #[test]
+    fn parse_drills() {
+        let drill = Drill::parse(
+            "bearoff 4HPwATDgc/ABMA cube: Double/Pass = 0.3, no double = 0, double/take = 0.05",
+        )
+        .unwrap();
+        assert_eq!(drill.category, Category::Bearoff);
+        assert_eq!(drill.question, Question::Cube);
+        assert_eq!(drill.answers[0].answer, "no double");
+        assert_eq!(drill.answer("double/pass"), Ok(drill.answers.last()));
+        assert_eq!(
+            drill.answer("beaver"),
+            Err(Error::InvalidCubeAction("beaver".to_string()))
+        );
+
+        let line = "opening 4HPwATDgc/ABMA 3-7: 8/5 6/5 = 0";
+        assert_eq!(
+            Drill::parse(line),
+            Err(Error::InvalidDrill(line.to_string()))
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let mut progress = Progress::new();
+        progress.record(Category::Opening, Some(0.0));
+        progress.record(Category::Opening, Some(0.1));
+        progress.start_session();
+        progress.record(Category::Opening, None);
+        progress.record(Category::Bearoff, Some(0.01));
+
+        let total = progress.total(Category::Opening);
+        assert_eq!((total.attempts, total.correct, total.scored), (3, 1, 2));
+        assert!((total.loss - 0.1).abs() < f64::EPSILON);
+        assert_eq!(Progress::parse(&progress.serialize()), Ok(progress.clone()));
+        assert!(
+            progress
+                .to_string()
+                .contains("opening   3 drills, 33% correct")
+        );
+        assert!(progress.to_string().contains("recent sessions 50% 0%"));
+    }
+}
@@ -0,0 +1,183 @@
+//! Variants of backgammon that differ from the standard game only in the
+//! starting position, so they are played with the same rules.
+
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::backgammon::{
+    Error,
+    board::{BOARD_SIZE, Board, CHECKERS},
+    player::Player,
+};
+
+/// The number of pieces on each point, from the perspective of the player
+/// owning them.
+type Points = [(usize, u8)];
+
+const STANDARD: &Points = &[(24, 2), (13, 5), (8, 3), (6, 5)];
+const NACKGAMMON: &Points = &[(24, 2), (23, 2), (13, 4), (8, 3), (6, 4)];
+const HYPERGAMMON: &Points = &[(24, 1), (23, 1), (22, 1)];
+const LONGGAMMON: &Points = &[(24, 15)];
+
+/// A starting position, which both players share from their own perspective.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+    #[default]
+    Standard,
+    /// Two pieces moved from the mid-point and the 6-point to the 23-point.
+    Nackgammon,
+    /// Three pieces each, on the 24-, 23- and 22-points.
+    Hypergammon,
+    /// Every piece starts on the 24-point.
+    Longgammon,
+    /// The given number of pieces on each point, written as
+    /// `<point>:<count>` pairs separated by commas.
+    Custom(Vec<(usize, u8)>),
+}
+
+impl Variant {
+    /// Gets the number of pieces each player starts with on each point.
+    fn points(&self) -> &Points {
+        match self {
+            Self::Standard => STANDARD,
+            Self::Nackgammon => NACKGAMMON,
+            Self::Hypergammon => HYPERGAMMON,
+            Self::Longgammon => LONGGAMMON,
+            Self::Custom(points) => points,
+        }
+    }
+
+    /// Creates the starting position of the variant.
+    #[must_use]
+    pub fn board(&self) -> Board {
+        let mut board = Board::empty();
+        let points = self.points();
+        board.set_checkers(points.iter().map(|&(_, count)| u32::from(count)).sum());
+        for &(point, count) in points {
+            board.point_mut(point - 1).set(count, Player::Black);
+            board
+                .point_mut(BOARD_SIZE - point)
+                .set(count, Player::White);
+        }
+        board
+    }
+
+    /// Parses a custom layout, which needs between one and 15 pieces on
+    /// distinct points that do not face a point of the same layout, as the
+    /// opponent's pieces start there.
+    fn parse_custom(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidVariant(s.to_owned());
+        let mut points: Vec<(usize, u8)> = vec![];
+        for pair in s.split(',').map(str::trim) {
+            let (point, count) = pair.split_once(':').ok_or_else(invalid)?;
+            let point = match point.trim().parse::<usize>() {
+                Ok(point) if (1..=BOARD_SIZE).contains(&point) => point,
+                _ => return Err(invalid()),
+            };
+            let count = match count.trim().parse::<u8>() {
+                Ok(count) if count > 0 => count,
+                _ => return Err(invalid()),
+            };
+            if points
+                .iter()
+                .any(|&(other, _)| other == point || other == BOARD_SIZE + 1 - point)
+            {
+                return Err(invalid());
+            }
+            points.push((point, count));
+        }
+
+        let total: u32 = points.iter().map(|&(_, count)| u32::from(count)).sum();
+        if total > CHECKERS {
+            return Err(invalid());
+        }
+        points.sort_by_key(|&(point, _)| Reverse(point));
+        Ok(Self::Custom(points))
+    }
+}
+
+impl FromStr for Variant {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" | "backgammon" => Ok(Self::Standard),
+            "nackgammon" => Ok(Self::Nackgammon),
+            "hypergammon" => Ok(Self::Hypergammon),
+            "longgammon" => Ok(Self::Longgammon),
+            _ => Self::parse_custom(s),
+        }
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Standard => f.write_str("standard"),
+            Self::Nackgammon => f.write_str("nackgammon"),
+            Self::Hypergammon => f.write_str("hypergammon"),
+            Self::Longgammon => f.write_str("longgammon"),
+            Self::Custom(points) => {
+                let points: Vec<_> = points
+                    .iter()
+                    .map(|(point, count)| format!("{point}:{count}"))
+                    .collect();
+                f.write_str(&points.join(","))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backgammon::{dice_roll::DiceRoll, game::Game};
+
+    #[test]
+    fn starting_positions() {
+        assert_eq!(Variant::Standard.board(), Board::new());
+        assert_eq!(Variant::Nackgammon.board().validate(), Ok(()));
+        assert_eq!(Variant::Longgammon.board().validate(), Ok(()));
+        assert_eq!(Variant::Hypergammon.board().validate(), Ok(()));
+
+        let board = Variant::Nackgammon.board();
+        assert_eq!(board.point(22).count, 2);
+        assert_eq!(board.point(1).player, Player::White);
+
+        let board = Variant::Hypergammon.board();
+        for player in [Player::Black, Player::White] {
+            assert_eq!(board.checker_count(player), 3);
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("Nackgammon".parse(), Ok(Variant::Nackgammon));
+        assert_eq!(
+            "6:5, 24:2".parse(),
+            Ok(Variant::Custom(vec![(24, 2), (6, 5)]))
+        );
+        assert_eq!(
+            "24:2,6:5".parse::<Variant>().unwrap().to_string(),
+            "24:2,6:5"
+        );
+        for invalid in ["24:16", "25:1", "6:0", "24:1,1:1", "6:1,6:1", "tavla"] {
+            assert_eq!(
+                invalid.parse::<Variant>(),
+                Err(Error::InvalidVariant(invalid.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn plays_with_standard_rules() {
+        let player = Player::Black;
+        let mut game = Game::from(player, DiceRoll::from([6, 5]), Variant::Hypergammon.board());
+        game.take_turn(&game.read_turn("24/18 23/18").unwrap());
+        assert_eq!(game.board.point(17).count, 2);
+
+        let game = Game::from(player, DiceRoll::from([6, 6]), Variant::Longgammon.board());
+        assert!(game.read_turn("24/18(4)").is_ok());
+    }
+}
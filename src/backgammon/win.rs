@@ -0,0 +1,100 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::backgammon::{Error, board::Board, compact::CompactBoard, player::Player};
+
+/// The first location, from a player's perspective, inside their opponent's
+/// home board.
+const OPPONENT_HOME: usize = 19;
+
+/// The level of a win, which is also what a player offers when resigning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Win {
+    /// The loser has borne off at least one piece.
+    Single,
+    /// The loser has not borne off any pieces.
+    Gammon,
+    /// The loser has not borne off any pieces and still has one on the bar or
+    /// in the winner's home board.
+    Backgammon,
+}
+
+impl Win {
+    /// Gets the level the winner would win at if the game ended now.
+    #[must_use]
+    pub fn of(board: &Board, winner: Player) -> Self {
+        let loser = !winner;
+        let pieces = *CompactBoard::from(board).pieces(loser);
+
+        if board.rail(loser).count > 0 {
+            Self::Single
+        } else if pieces[OPPONENT_HOME..].iter().any(|&count| count > 0) {
+            Self::Backgammon
+        } else {
+            Self::Gammon
+        }
+    }
+
+    /// Gets the number of points the win is worth.
+    #[must_use]
+    pub const fn points(self) -> u8 {
+        self as u8 + 1
+    }
+}
+
+impl FromStr for Win {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "single" => Ok(Self::Single),
+            "gammon" => Ok(Self::Gammon),
+            "backgammon" => Ok(Self::Backgammon),
+            _ => Err(Error::InvalidWin(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Win {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Single => "single game",
+            Self::Gammon => "gammon",
+            Self::Backgammon => "backgammon",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("".parse(), Ok(Win::Single));
+        assert_eq!("Gammon".parse(), Ok(Win::Gammon));
+        assert_eq!("backgammon".parse(), Ok(Win::Backgammon));
+        assert_eq!(
+            "double".parse::<Win>(),
+            Err(Error::InvalidWin("double".to_string()))
+        );
+    }
+
+    #[test]
+    fn of() {
+        let player = Player::Black;
+        let mut board = Board::empty();
+        board.rail_mut(player).set(15, player);
+        board.point_mut(12).set(15, !player);
+        assert_eq!(Win::of(&board, player), Win::Gammon);
+
+        board.point_mut(12).set(14, !player);
+        board.point_mut(2).set(1, !player);
+        assert_eq!(Win::of(&board, player), Win::Backgammon);
+
+        board.point_mut(2).set(0, Player::None);
+        board.rail_mut(!player).set(1, !player);
+        assert_eq!(Win::of(&board, player), Win::Single);
+        assert_eq!(Win::Backgammon.points(), 3);
+    }
+}
@@ -1,13 +1,437 @@
-use backgammon_cli::backgammon::Game;
-use std::env;
+use backgammon_cli::backgammon::{
+    Book, Category, DiceStats, Drill, Game, Player, Profiles, Progress, Rules, Setup, Strategy,
+    TAVLI, Theme, Tournament, Variant, divide, perft, train,
+};
+use std::{env, fmt::Display, net::TcpListener, path::Path, process, str::FromStr, sync::Arc};
+
+/// The options given on the command line.
+struct Options {
+    auto_play: bool,
+    auto_bear_off: bool,
+    book: Arc<Book>,
+    bot: Option<Player>,
+    external: Option<String>,
+    /// The profile names of `Player::Black` and `Player::White` respectively
+    names: [Option<String>; 2],
+    perft_args: Option<(String, Option<u32>)>,
+    rules: Rules,
+    command: Option<Command>,
+    setup: bool,
+    strategy: Strategy,
+    theme: Theme,
+    variant: Variant,
+}
+
+impl Options {
+    /// Parses the command line arguments, exiting if they are not valid.
+    fn parse() -> Self {
+        let mut options = Self {
+            auto_play: true,
+            auto_bear_off: false,
+            book: Arc::new(Book::load().unwrap_or_else(|error| exit(error))),
+            bot: None,
+            external: None,
+            names: [None, None],
+            perft_args: None,
+            rules: Rules::default(),
+            command: None,
+            setup: false,
+            strategy: Strategy::default(),
+            theme: Theme::load().unwrap_or_else(|error| exit(error)),
+            variant: Variant::default(),
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--debug" => unsafe {
+                    env::set_var("RUST_BACKTRACE", "1");
+                },
+                "setup" => options.setup = true,
+                "stats" => options.command = Some(Command::Stats),
+                "leaderboard" => options.command = Some(Command::Leaderboard),
+                "profile" => {
+                    let name = expect_value(&arg, args.next());
+                    options.command = Some(Command::Profile(name));
+                }
+                "record" => {
+                    let winner = expect_value(&arg, args.next());
+                    let loser = expect_value(&arg, args.next());
+                    let length = args.next().map_or(1, |length| match length.parse::<u32>() {
+                        Ok(length) if length > 0 => length,
+                        _ => exit(format!("match length '{length}' is not valid")),
+                    });
+                    options.command = Some(Command::Record(winner, loser, length));
+                }
+                "tavli" => {
+                    let length = expect_value(&arg, args.next());
+                    let length = match length.parse::<u32>() {
+                        Ok(length) if length > 0 => length,
+                        _ => exit(format!("match length '{length}' is not valid")),
+                    };
+                    options.command = Some(Command::Tavli(length));
+                }
+                "book" => options.command = Some(Command::Book(args.by_ref().collect())),
+                "train" => options.command = Some(Command::Train(args.by_ref().collect())),
+                "tournament" => {
+                    options.command = Some(Command::Tournament(args.by_ref().collect()));
+                }
+                "--black" => options.names[0] = Some(expect_value(&arg, args.next())),
+                "--white" => options.names[1] = Some(expect_value(&arg, args.next())),
+                "--no-auto-play" => options.auto_play = false,
+                "--auto-bear-off" => options.auto_bear_off = true,
+                "perft" => {
+                    let position_id = expect_value(&arg, args.next());
+                    let depth = args.next().map(|depth| {
+                        depth
+                            .parse::<u32>()
+                            .unwrap_or_else(|_| exit(format!("depth '{depth}' is not valid")))
+                    });
+                    options.perft_args = Some((position_id, depth));
+                }
+                "--bot" => options.bot = Some(parse_value(&arg, args.next())),
+                "--external" => options.external = Some(expect_value(&arg, args.next())),
+                "--rules" => options.rules = parse_value(&arg, args.next()),
+                "--variant" => options.variant = parse_value(&arg, args.next()),
+                "--strategy" => options.strategy = parse_value(&arg, args.next()),
+                "--borders" => options.theme.borders = parse_value(&arg, args.next()),
+                "--layout" => options.theme.layout = parse_value(&arg, args.next()),
+                "--palette" => options.theme.palette = parse_value(&arg, args.next()),
+                _ => exit(format!("unknown argument '{arg}'")),
+            }
+        }
+        if options.rules != Rules::Standard && options.variant != Variant::Standard {
+            exit("variants can only be played with standard rules");
+        }
+        options
+    }
+
+    /// Creates a new game by the given rules, starting from the position of
+    /// the variant of the options under standard rules.
+    fn new_game(&self, rules: Rules) -> Game {
+        if rules == Rules::Standard {
+            Game::new().with_variant(&self.variant)
+        } else {
+            Game::new().with_rules(rules)
+        }
+    }
+}
 
 fn main() {
-    if env::args().nth(1).as_deref() == Some("--debug") {
-        unsafe {
-            env::set_var("RUST_BACKTRACE", "1");
+    let mut options = Options::parse();
+    options.theme.set();
+
+    let dice_stats = DiceStats::load().unwrap_or_else(|error| exit(error));
+    let mut profiles = Profiles::load().unwrap_or_else(|error| exit(error));
+    if let Some(command) = options.command.take() {
+        run_command(command, &options, &dice_stats, &mut profiles);
+        return;
+    }
+    let names = match std::mem::take(&mut options.names) {
+        [Some(black), Some(white)] => Some([black, white]),
+        [None, None] => None,
+        _ => exit("expected both '--black' and '--white'"),
+    };
+
+    if let Some((position_id, depth)) = options.perft_args {
+        run_perft(&position_id, depth);
+        return;
+    }
+
+    if let Some(addr) = options.external {
+        let listener = TcpListener::bind(&addr).unwrap_or_else(|error| exit(error));
+        println!("Serving {} external player on {addr}", options.strategy);
+        backgammon_cli::backgammon::serve(&listener, options.strategy);
+        return;
+    }
+
+    let mut game = if options.setup {
+        Setup::new().start().unwrap_or_else(|error| exit(error))
+    } else {
+        options.new_game(options.rules)
+    };
+    game = game
+        .with_auto_play(options.auto_play)
+        .with_auto_bear_off(options.auto_bear_off)
+        .with_dice_stats(dice_stats)
+        .with_book(Arc::clone(&options.book));
+    if let Some(player) = options.bot {
+        game = game.with_bot(player, options.strategy);
+    }
+    let result = game.start();
+
+    if let (Some(names), Some((winner, _))) = (names, result) {
+        let [winner, loser] = [winner, !winner].map(|player| &names[player as usize]);
+        record_match(&mut profiles, winner, loser, 1);
+    }
+}
+
+/// A subcommand reading or updating the files kept between sessions.
+enum Command {
+    Stats,
+    Leaderboard,
+    /// Shows a profile with its results against each opponent
+    Profile(String),
+    /// Records a match played elsewhere by its winner, loser and length
+    Record(String, String, u32),
+    /// Shows or writes the opening book as given by the rest of the arguments
+    Book(Vec<String>),
+    /// Runs the tournament action given by the rest of the arguments
+    Tournament(Vec<String>),
+    /// Plays a tavli match of the given length, cycling through its games
+    Tavli(u32),
+    /// Trains with drills, or shows the progress, as given by the rest of the
+    /// arguments
+    Train(Vec<String>),
+}
+
+fn run_command(
+    command: Command,
+    options: &Options,
+    dice_stats: &DiceStats,
+    profiles: &mut Profiles,
+) {
+    match command {
+        Command::Stats => println!("{dice_stats}"),
+        Command::Leaderboard => println!("{profiles}"),
+        Command::Profile(name) => {
+            println!(
+                "{}",
+                profiles.get(&name).unwrap_or_else(|error| exit(error))
+            );
         }
+        Command::Record(winner, loser, length) => {
+            record_match(profiles, &winner, &loser, length);
+        }
+        Command::Book(args) => match args.as_slice() {
+            [] => println!("{}", options.book),
+            [action, path] if action == "write" => {
+                options
+                    .book
+                    .write(Path::new(path))
+                    .unwrap_or_else(|error| exit(error));
+                println!("wrote opening book to {path}");
+            }
+            _ => exit(format!("book action '{}' is not valid", args.join(" "))),
+        },
+        Command::Tournament(args) => run_tournament(&args, options, profiles),
+        Command::Tavli(length) => run_tavli(length, options, profiles),
+        Command::Train(args) => run_training(&args),
+    }
+}
+
+/// Trains with the built-in drills or those of a file, limited to the given
+/// categories if any, or shows the progress so far.
+fn run_training(args: &[String]) {
+    let mut progress = Progress::load().unwrap_or_else(|error| exit(error));
+    if args == ["progress"] {
+        println!("{progress}");
+        return;
+    }
+
+    let mut categories = vec![];
+    let mut drills = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--category" => categories.push(parse_value::<Category>(arg, args.next().cloned())),
+            path => drills = Some(Drill::read(Path::new(path)).unwrap_or_else(|error| exit(error))),
+        }
+    }
+
+    let mut drills = drills.unwrap_or_else(Drill::builtin);
+    if !categories.is_empty() {
+        drills.retain(|drill| categories.contains(&drill.category));
+    }
+    if drills.is_empty() {
+        exit("there are no drills to train with");
     }
+    train(drills, &mut progress);
+    println!("\n{progress}");
+}
+
+/// Creates, pairs, records, plays or shows the tournament, saving it after
+/// every change.
+fn run_tournament(args: &[String], options: &Options, profiles: &mut Profiles) {
+    let args: Vec<_> = args.iter().map(String::as_str).collect();
+    let load = || Tournament::load().unwrap_or_else(|error| exit(error));
+
+    let tournament = match args.as_slice() {
+        [] => load(),
+        ["new", format, lengths, players @ ..] => {
+            let format = format.parse().unwrap_or_else(|error| exit(error));
+            let lengths = lengths
+                .split(',')
+                .map(|length| match length.parse::<u32>() {
+                    Ok(length) if length > 0 => length,
+                    _ => exit(format!("match length '{length}' is not valid")),
+                })
+                .collect();
+            let players: Vec<_> = players.iter().map(ToString::to_string).collect();
+            Tournament::create(format, lengths, &players).unwrap_or_else(|error| exit(error))
+        }
+        ["register", players @ ..] => {
+            let mut tournament = load();
+            for player in players {
+                tournament
+                    .register(player)
+                    .unwrap_or_else(|error| exit(error));
+            }
+            tournament
+        }
+        ["pair"] => {
+            let mut tournament = load();
+            tournament.pair().unwrap_or_else(|error| exit(error));
+            tournament
+        }
+        ["result", winner, loser] => {
+            let mut tournament = load();
+            let length = tournament
+                .record(winner, loser)
+                .unwrap_or_else(|error| exit(error));
+            record_match(profiles, winner, loser, length);
+            tournament
+        }
+        ["play"] => {
+            let mut tournament = load();
+            let Some(pairing) = tournament.pending().next().cloned() else {
+                exit(format!(
+                    "round {} has no matches to play",
+                    tournament.round()
+                ));
+            };
+            let rules = [options.rules];
+            let Some(winner) = play_match(&pairing.players, pairing.length, &rules, options) else {
+                return;
+            };
+            let [winner, loser] = [winner, !winner].map(|player| &pairing.players[player as usize]);
+            tournament
+                .record(winner, loser)
+                .unwrap_or_else(|error| exit(error));
+            record_match(profiles, winner, loser, pairing.length);
+            tournament
+        }
+        _ => exit(format!(
+            "tournament action '{}' is not valid",
+            args.join(" ")
+        )),
+    };
+
+    tournament.save().unwrap_or_else(|error| exit(error));
+    println!("{tournament}");
+}
+
+/// Plays a tavli match between the named players, or Black and White, and
+/// records it if the players are named.
+fn run_tavli(length: u32, options: &Options, profiles: &mut Profiles) {
+    let names = match &options.names {
+        [Some(black), Some(white)] => Some([black.clone(), white.clone()]),
+        [None, None] => None,
+        _ => exit("expected both '--black' and '--white'"),
+    };
+    let players = names
+        .clone()
+        .unwrap_or_else(|| [Player::Black, Player::White].map(|player| player.to_string()));
+
+    let Some(winner) = play_match(&players, length, &TAVLI, options) else {
+        return;
+    };
+    if let Some(names) = names {
+        let [winner, loser] = [winner, !winner].map(|player| &names[player as usize]);
+        record_match(profiles, winner, loser, length);
+    }
+}
+
+/// Plays games between two players until one reaches the match length,
+/// returning the winner, or nothing if input ends first. Games are played by
+/// each of the given rules in turn.
+fn play_match(
+    players: &[String; 2],
+    length: u32,
+    rules: &[Rules],
+    options: &Options,
+) -> Option<Player> {
+    let [black, white] = players;
+    println!("{black} plays Black and {white} plays White in a {length} point match");
+
+    let mut score = [0, 0];
+    for &rules in rules.iter().cycle() {
+        if score.iter().any(|&points| points >= length) {
+            break;
+        }
+        if rules != Rules::Standard {
+            println!("\nPlaying {rules}");
+        }
+
+        let dice_stats = DiceStats::load().unwrap_or_else(|error| exit(error));
+        let mut game = options
+            .new_game(rules)
+            .with_auto_play(options.auto_play)
+            .with_auto_bear_off(options.auto_bear_off)
+            .with_dice_stats(dice_stats)
+            .with_book(Arc::clone(&options.book));
+        if let Some(player) = options.bot {
+            game = game.with_bot(player, options.strategy);
+        }
+        let (winner, win) = game.start()?;
+        score[winner as usize] += u32::from(win.points());
+        println!(
+            "\n{black} {} - {} {white}, match to {length}",
+            score[0], score[1]
+        );
+    }
+    Some(if score[0] >= length {
+        Player::Black
+    } else {
+        Player::White
+    })
+}
+
+/// Records a match between two profiles, saves them and prints the rating
+/// changes.
+fn record_match(profiles: &mut Profiles, winner: &str, loser: &str, length: u32) {
+    let (gain, loss) = profiles
+        .record_match(winner, loser, length)
+        .unwrap_or_else(|error| exit(error));
+    profiles.save().unwrap_or_else(|error| exit(error));
+    for (name, change) in [(winner, gain), (loser, loss)] {
+        let rating = profiles
+            .get(name)
+            .map(|profile| profile.rating)
+            .unwrap_or_default();
+        println!("{name}: {rating:.2} ({change:+.2})");
+    }
+}
+
+/// Prints the number of turns from a position for each roll, followed by the
+/// leaf count of the game tree when a depth is given.
+fn run_perft(position_id: &str, depth: Option<u32>) {
+    let counts = divide(position_id).unwrap_or_else(|error| exit(error));
+    for ([a, b], count) in &counts {
+        println!("{a}-{b}: {count}");
+    }
+    println!(
+        "total: {}",
+        counts.iter().map(|(_, count)| count).sum::<usize>()
+    );
+
+    if let Some(depth) = depth {
+        let count = perft(position_id, depth).unwrap_or_else(|error| exit(error));
+        println!("perft({depth}): {count}");
+    }
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> String {
+    value.unwrap_or_else(|| exit(format!("expected a value after '{flag}'")))
+}
+
+fn parse_value<T: FromStr<Err: Display>>(flag: &str, value: Option<String>) -> T {
+    expect_value(flag, value)
+        .parse()
+        .unwrap_or_else(|error| exit(error))
+}
 
-    let mut game = Game::new();
-    game.start();
+fn exit(error: impl Display) -> ! {
+    eprintln!("error: {error}");
+    process::exit(1);
 }
@@ -0,0 +1,143 @@
+#!/usr/bin/env python3
+"""Regenerates the reference counts in perft.txt.
+
+This is a deliberately separate implementation of the backgammon move rules,
+sharing no code with the crate, so the fixtures check the crate's move
+generator against an independent reading of the rules rather than against
+itself. Run it from the repository root:
+
+    python3 tests/fixtures/perft.py > tests/fixtures/perft.txt
+"""
+
+import base64
+
+POSITIONS = [
+    # The opening.
+    "4HPwATDgc/ABMA",
+    # A player on the bar against a four point board.
+    "yHPwATDgOfgAWA",
+    # A bear-off against a single opposing blot.
+    "/z8AAATnMQAAAA",
+]
+ROLLS = [(a, b) for a in range(1, 7) for b in range(a, 7)]
+BAR = 25
+
+
+def decode(position_id):
+    """Decodes a GNU Backgammon position ID into the checkers of the player on
+    roll and of their opponent, each indexed from their own perspective with
+    0 the checkers borne off, 1 to 24 the points and 25 the bar. The opponent
+    is encoded first, and checkers borne off are not."""
+    key = base64.b64decode(position_id + "==")
+    bits = [(byte >> bit) & 1 for byte in key for bit in range(8)]
+    sides, counts, count = [], [], 0
+    for bit in bits:
+        if bit:
+            count += 1
+            continue
+        counts.append(count)
+        count = 0
+        if len(counts) == 25:
+            sides.append((0, *counts))
+            counts = []
+            if len(sides) == 2:
+                break
+    opponent, player = sides
+    return player, opponent
+
+
+def moves(player, opponent, die):
+    """Gets every position after moving one checker of the player by a die."""
+    found = []
+    if player[BAR]:
+        sources = [BAR]
+    else:
+        sources = [point for point in range(1, 25) if player[point]]
+    home = all(player[point] == 0 for point in range(7, BAR + 1))
+
+    for source in sources:
+        target = source - die
+        if target <= 0:
+            if not home:
+                continue
+            if target < 0 and any(player[point] for point in range(source + 1, 7)):
+                continue
+            target = 0
+        across = 25 - target
+        if target and opponent[across] >= 2:
+            continue
+
+        mine, theirs = list(player), list(opponent)
+        mine[source] -= 1
+        mine[target] += 1
+        if target and theirs[across] == 1:
+            theirs[across] = 0
+            theirs[BAR] += 1
+        found.append((tuple(mine), tuple(theirs)))
+    return found
+
+
+def turns(player, opponent, roll):
+    """Gets the distinct positions after every legal turn of a roll, playing as
+    many dice as possible and the larger die when only one can be played."""
+    a, b = roll
+    orders = [(a,) * 4] if a == b else [(a, b), (b, a)]
+    # The positions after each number of dice, with the first die played.
+    ends = {}
+    for order in orders:
+        level = {(player, opponent)}
+        for used, die in enumerate(order):
+            following = set()
+            for state in level:
+                after = moves(*state, die)
+                if not after:
+                    ends.setdefault(used, set()).add((state, order[0]))
+                following.update(after)
+            level = following
+            if not level:
+                break
+        else:
+            ends.setdefault(len(order), set()).update((state, order[0]) for state in level)
+
+    most = max(ends)
+    found = ends[most]
+    if most == 1 and a != b:
+        larger = {end for end in found if end[1] == max(a, b)}
+        found = larger or found
+    return {state for state, _ in found}
+
+
+def count(player, opponent, depth):
+    """Counts the leaves of the game tree, branching on the 21 rolls and then
+    each distinct turn, where positions with the game over are leaves."""
+    if depth == 0 or sum(opponent[1:]) == 0:
+        return 1
+    return sum(
+        count(theirs, mine, depth - 1)
+        for roll in ROLLS
+        for mine, theirs in turns(player, opponent, roll)
+    )
+
+
+def main():
+    print("# Reference counts for move generation, with the player of each GNU Backgammon")
+    print("# position ID on roll, generated by tests/fixtures/perft.py, an implementation")
+    print("# of the rules written separately from the crate. They have not been compared")
+    print("# with GNU Backgammon.")
+    print("#")
+    print("#   divide <position-id> <roll> <distinct turns>")
+    print("#   perft <position-id> <depth> <leaves>")
+    print("#")
+    print("# Positions: the opening, a player on the bar against a four point board, and")
+    print("# a bear-off against a single opposing blot.")
+    for position_id in POSITIONS:
+        player, opponent = decode(position_id)
+        print()
+        for roll in ROLLS:
+            print(f"divide {position_id} {roll[0]}-{roll[1]} {len(turns(player, opponent, roll))}")
+        for depth in (1, 2):
+            print(f"perft {position_id} {depth} {count(player, opponent, depth)}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,46 @@
+use backgammon_cli::backgammon::{divide, perft};
+
+const FIXTURES: &str = include_str!("fixtures/perft.txt");
+
+/// Gets the fields of each fixture of the given kind.
+fn fixtures(kind: &str) -> impl Iterator<Item = (&str, &str, u64)> {
+    FIXTURES
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(
+            move |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [k, id, key, count] if k == kind => Some((id, key, count.parse().unwrap())),
+                [_, _, _, _] => None,
+                _ => panic!("malformed fixture '{line}'"),
+            },
+        )
+}
+
+#[test]
+fn divide_matches_fixtures() {
+    let mut checked = 0;
+    for (id, roll, expected) in fixtures("divide") {
+        let count = divide(id)
+            .unwrap()
+            .into_iter()
+            .find(|([a, b], _)| format!("{a}-{b}") == roll)
+            .map(|(_, count)| count as u64);
+        assert_eq!(count, Some(expected), "{id} with {roll}");
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn perft_matches_fixtures() {
+    let mut checked = 0;
+    for (id, depth, expected) in fixtures("perft") {
+        assert_eq!(
+            perft(id, depth.parse().unwrap()),
+            Ok(expected),
+            "{id} at {depth}"
+        );
+        checked += 1;
+    }
+    assert!(checked > 0);
+}